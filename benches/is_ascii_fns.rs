@@ -1,4 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use is_ascii_speedups::table::IsAsciiTable;
 use is_ascii_speedups::IsAscii2;
 use rand::distributions::Standard;
 use rand::{thread_rng, Rng};
@@ -9,6 +10,7 @@ macro_rules! ascii_benches {
         $this_method_u8s: ident,
         $old_method: ident,
         $new_method: ident,
+        $table_method: ident,
         $c: ident,
         $ascii_u8s: ident,
         $random_u8s: ident,
@@ -24,6 +26,10 @@ macro_rules! ascii_benches {
             b.iter(|| black_box($random_u8s.next().unwrap()).$new_method())
         });
 
+        $c.bench_function(concat!("table_", stringify!($name), "_random_u8s"), |b| {
+            b.iter(|| black_box($random_u8s.next().unwrap()).$table_method())
+        });
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_random_ascii_u8s"),
             |b| b.iter(|| black_box($ascii_u8s.next().unwrap()).$old_method()),
@@ -34,6 +40,11 @@ macro_rules! ascii_benches {
             |b| b.iter(|| black_box($ascii_u8s.next().unwrap()).$new_method()),
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_random_ascii_u8s"),
+            |b| b.iter(|| black_box($ascii_u8s.next().unwrap()).$table_method()),
+        );
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_count_starting_u8_matches"),
             |b| {
@@ -60,6 +71,24 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_count_starting_u8_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend($this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take(64 - length));
+                    black_box(
+                        $u8_string
+                            .iter()
+                            .take_while(|ch| ch.$table_method())
+                            .count(),
+                    )
+                })
+            },
+        );
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_count_all_u8_matches"),
             |b| {
@@ -86,6 +115,19 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_count_all_u8_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend($this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take(64 - length));
+                    black_box($u8_string.iter().filter(|ch| ch.$table_method()).count())
+                })
+            },
+        );
+
         $c.bench_function(
             concat!(
                 "old_",
@@ -122,6 +164,29 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!(
+                "table_",
+                stringify!($name),
+                "_count_starting_u8_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend($this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take((2 << 20) - length));
+                    black_box(
+                        $u8_string
+                            .iter()
+                            .take_while(|ch| ch.$table_method())
+                            .count(),
+                    )
+                })
+            },
+        );
+
         $c.bench_function(
             concat!(
                 "old_",
@@ -158,6 +223,24 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!(
+                "table_",
+                stringify!($name),
+                "_count_all_u8_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend($this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take((2 << 20) - length));
+                    black_box($u8_string.iter().filter(|ch| ch.$table_method()).count())
+                })
+            },
+        );
+
         $c.bench_function(concat!("old_", stringify!($name), "_random_chars"), |b| {
             b.iter(|| black_box($random_chars.next().unwrap()).$old_method())
         });
@@ -166,6 +249,10 @@ macro_rules! ascii_benches {
             b.iter(|| black_box($random_chars.next().unwrap()).$new_method())
         });
 
+        $c.bench_function(concat!("table_", stringify!($name), "_random_chars"), |b| {
+            b.iter(|| black_box($random_chars.next().unwrap()).$table_method())
+        });
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_random_ascii_chars"),
             |b| {
@@ -186,6 +273,16 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_random_ascii_chars"),
+            |b| {
+                b.iter(|| {
+                    black_box($ascii_u8s.by_ref().map(|ch| char::from(ch)).next().unwrap())
+                        .$table_method()
+                })
+            },
+        );
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_count_starting_char_matches"),
             |b| {
@@ -232,6 +329,29 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_count_starting_char_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $char_string.clear();
+                    $char_string.extend(
+                        $this_method_u8s
+                            .by_ref()
+                            .map(|ch| char::from(ch))
+                            .take(length),
+                    );
+                    $char_string.extend($random_chars.by_ref().take(64 - length));
+                    black_box(
+                        $char_string
+                            .chars()
+                            .take_while(|ch| ch.$table_method())
+                            .count(),
+                    )
+                })
+            },
+        );
+
         $c.bench_function(
             concat!("old_", stringify!($name), "_count_all_char_matches"),
             |b| {
@@ -268,6 +388,24 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!("table_", stringify!($name), "_count_all_char_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $char_string.clear();
+                    $char_string.extend(
+                        $this_method_u8s
+                            .by_ref()
+                            .map(|ch| char::from(ch))
+                            .take(length),
+                    );
+                    $char_string.extend($random_chars.by_ref().take(64 - length));
+                    black_box($char_string.chars().filter(|ch| ch.$table_method()).count())
+                })
+            },
+        );
+
         $c.bench_function(
             concat!(
                 "old_",
@@ -324,6 +462,34 @@ macro_rules! ascii_benches {
             },
         );
 
+        $c.bench_function(
+            concat!(
+                "table_",
+                stringify!($name),
+                "_count_starting_char_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $char_string.clear();
+                    $char_string.extend(
+                        $this_method_u8s
+                            .by_ref()
+                            .map(|ch| char::from(ch))
+                            .take(length),
+                    );
+                    $char_string.extend($random_chars.by_ref().take((2 << 20) - length));
+                    black_box(
+                        $char_string
+                            .chars()
+                            .take_while(|ch| ch.$table_method())
+                            .count(),
+                    )
+                })
+            },
+        );
+
         $c.bench_function(
             concat!(
                 "old_",
@@ -369,6 +535,29 @@ macro_rules! ascii_benches {
                 })
             },
         );
+
+        $c.bench_function(
+            concat!(
+                "table_",
+                stringify!($name),
+                "_count_all_char_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $char_string.clear();
+                    $char_string.extend(
+                        $this_method_u8s
+                            .by_ref()
+                            .map(|ch| char::from(ch))
+                            .take(length),
+                    );
+                    $char_string.extend($random_chars.by_ref().take((2 << 20) - length));
+                    black_box($char_string.chars().filter(|ch| ch.$table_method()).count())
+                })
+            },
+        );
     };
 }
 
@@ -502,6 +691,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ascii_u8s,
         is_ascii,
         is_ascii_2,
+        is_ascii_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -515,6 +705,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         alphabetic_u8s,
         is_ascii_alphabetic,
         is_ascii_alphabetic_2,
+        is_ascii_alphabetic_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -528,6 +719,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         alphanumeric_u8s,
         is_ascii_alphanumeric,
         is_ascii_alphanumeric_2,
+        is_ascii_alphanumeric_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -541,6 +733,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         control_u8s,
         is_ascii_control,
         is_ascii_control_2,
+        is_ascii_control_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -554,6 +747,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         digit_u8s,
         is_ascii_digit,
         is_ascii_digit_2,
+        is_ascii_digit_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -567,6 +761,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         graphic_u8s,
         is_ascii_graphic,
         is_ascii_graphic_2,
+        is_ascii_graphic_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -580,6 +775,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         hexdigit_u8s,
         is_ascii_hexdigit,
         is_ascii_hexdigit_2,
+        is_ascii_hexdigit_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -593,6 +789,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         lowercase_u8s,
         is_ascii_lowercase,
         is_ascii_lowercase_2,
+        is_ascii_lowercase_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -606,6 +803,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         punctuation_u8s,
         is_ascii_punctuation,
         is_ascii_punctuation_2,
+        is_ascii_punctuation_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -619,6 +817,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         uppercase_u8s,
         is_ascii_uppercase,
         is_ascii_uppercase_2,
+        is_ascii_uppercase_table,
         c,
         ascii_u8s,
         random_u8s,
@@ -632,6 +831,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         whitespace_u8s,
         is_ascii_whitespace,
         is_ascii_whitespace_2,
+        is_ascii_whitespace_table,
         c,
         ascii_u8s,
         random_u8s,
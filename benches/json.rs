@@ -0,0 +1,83 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use is_ascii_speedups::json::escape_json_2;
+use rand::{thread_rng, Rng};
+use std::fmt::Write;
+
+// Escapes one `char` at a time, as a straightforward serializer would.
+fn escape_json_per_byte<W: Write>(s: &str, out: &mut W) -> std::fmt::Result {
+    for ch in s.chars() {
+        match ch {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\x08' => out.write_str("\\b")?,
+            '\x0C' => out.write_str("\\f")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\0'..='\x1F' => write!(out, "\\u{:04x}", ch as u32)?,
+            _ => out.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
+
+macro_rules! json_benches {
+    ($name: ident, $c: ident, $input: ident, $output: ident) => {
+        $c.bench_function(concat!("old_escape_json_", stringify!($name)), |b| {
+            b.iter(|| {
+                $output.clear();
+                escape_json_per_byte(black_box(&$input), &mut $output).unwrap();
+                black_box($output.len())
+            })
+        });
+
+        $c.bench_function(concat!("new_escape_json_", stringify!($name)), |b| {
+            b.iter(|| {
+                $output.clear();
+                escape_json_2(black_box(&$input), &mut $output).unwrap();
+                black_box($output.len())
+            })
+        });
+    };
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // Printable ASCII with, if `escape_every` isn't 0, an escape roughly every
+    // `escape_every` bytes.
+    let text = |len: usize, escape_every: u32| -> String {
+        let mut rng = thread_rng();
+        (0..len)
+            .map(|_| {
+                if escape_every != 0 && rng.gen_range(0..escape_every) == 0 {
+                    ['"', '\\', '\n', '\x01'][rng.gen_range(0..4)]
+                } else {
+                    loop {
+                        let ch = char::from(rng.gen_range(b' '..=b'~'));
+                        if ch != '"' && ch != '\\' {
+                            break ch;
+                        }
+                    }
+                }
+            })
+            .collect()
+    };
+
+    let short_clean = text(24, 0);
+    let short_escapes = text(24, 8);
+    let long_clean = text(4096, 0);
+    let long_escapes = text(4096, 32);
+
+    let mut output = String::with_capacity(4 * 4096);
+
+    json_benches!(short_clean, c, short_clean, output);
+    json_benches!(short_escapes, c, short_escapes, output);
+    json_benches!(long_clean, c, long_clean, output);
+    json_benches!(long_escapes, c, long_escapes, output);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
@@ -0,0 +1,241 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use is_ascii_speedups::split::SplitWhileAscii2;
+use rand::distributions::Standard;
+use rand::{thread_rng, Rng};
+
+// The `count_starting_*_matches` benchmarks of `is_ascii_fns`, with the new side
+// using `split_while_ascii_*_2` instead of `take_while`.
+macro_rules! split_while_benches {
+    (
+        $name: ident,
+        $old_method: ident,
+        $split_method: ident,
+        $c: ident,
+        $random_u8s: ident,
+        $u8_string: ident,
+        $char_string: ident,
+    ) => {
+        // Random bytes in the class, found by rejection.
+        let mut this_method_u8s = thread_rng()
+            .sample_iter::<u8, Standard>(Standard)
+            .filter(|ch| ch.$old_method());
+
+        $c.bench_function(
+            concat!("old_", stringify!($name), "_count_starting_u8_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend(this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take(64 - length));
+                    black_box($u8_string.iter().take_while(|ch| ch.$old_method()).count())
+                })
+            },
+        );
+
+        $c.bench_function(
+            concat!("new_", stringify!($name), "_count_starting_u8_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend(this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take(64 - length));
+                    black_box($u8_string.$split_method().0.len())
+                })
+            },
+        );
+
+        $c.bench_function(
+            concat!(
+                "old_",
+                stringify!($name),
+                "_count_starting_u8_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend(this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take((2 << 20) - length));
+                    black_box($u8_string.iter().take_while(|ch| ch.$old_method()).count())
+                })
+            },
+        );
+
+        $c.bench_function(
+            concat!(
+                "new_",
+                stringify!($name),
+                "_count_starting_u8_matches_cache_buster"
+            ),
+            |b| {
+                b.iter(|| {
+                    let length = (u32::from_ne_bytes([(); 4].map(|_| $random_u8s.next().unwrap()))
+                        % ((2 << 20) + 1)) as usize;
+                    $u8_string.clear();
+                    $u8_string.extend(this_method_u8s.by_ref().take(length));
+                    $u8_string.extend($random_u8s.by_ref().take((2 << 20) - length));
+                    black_box($u8_string.$split_method().0.len())
+                })
+            },
+        );
+
+        $c.bench_function(
+            concat!("old_", stringify!($name), "_count_starting_str_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $char_string.clear();
+                    $char_string.extend(this_method_u8s.by_ref().map(char::from).take(length));
+                    $char_string.extend($random_u8s.by_ref().map(char::from).take(64 - length));
+                    black_box(
+                        $char_string
+                            .bytes()
+                            .take_while(|ch| ch.$old_method())
+                            .count(),
+                    )
+                })
+            },
+        );
+
+        $c.bench_function(
+            concat!("new_", stringify!($name), "_count_starting_str_matches"),
+            |b| {
+                b.iter(|| {
+                    let length = ($random_u8s.next().unwrap() % 65) as usize;
+                    $char_string.clear();
+                    $char_string.extend(this_method_u8s.by_ref().map(char::from).take(length));
+                    $char_string.extend($random_u8s.by_ref().map(char::from).take(64 - length));
+                    black_box($char_string.as_str().$split_method().0.len())
+                })
+            },
+        );
+    };
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut random_u8s = thread_rng().sample_iter::<u8, Standard>(Standard);
+
+    // 2 MiB buffer to exceed the L2 cache size.
+    let mut u8_string: Vec<u8> = Vec::with_capacity(2 << 20);
+    let mut char_string = String::with_capacity(2 * 64);
+
+    split_while_benches!(
+        ascii,
+        is_ascii,
+        split_while_ascii_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        alphabetic,
+        is_ascii_alphabetic,
+        split_while_ascii_alphabetic_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        alphanumeric,
+        is_ascii_alphanumeric,
+        split_while_ascii_alphanumeric_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        control,
+        is_ascii_control,
+        split_while_ascii_control_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        digit,
+        is_ascii_digit,
+        split_while_ascii_digit_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        graphic,
+        is_ascii_graphic,
+        split_while_ascii_graphic_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        hexdigit,
+        is_ascii_hexdigit,
+        split_while_ascii_hexdigit_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        lowercase,
+        is_ascii_lowercase,
+        split_while_ascii_lowercase_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        punctuation,
+        is_ascii_punctuation,
+        split_while_ascii_punctuation_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        uppercase,
+        is_ascii_uppercase,
+        split_while_ascii_uppercase_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+
+    split_while_benches!(
+        whitespace,
+        is_ascii_whitespace,
+        split_while_ascii_whitespace_2,
+        c,
+        random_u8s,
+        u8_string,
+        char_string,
+    );
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
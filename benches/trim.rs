@@ -0,0 +1,73 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use is_ascii_speedups::trim::TrimAsciiWhitespace2;
+use rand::{thread_rng, Rng};
+
+macro_rules! trim_benches {
+    ($name: ident, $c: ident, $input: ident) => {
+        $c.bench_function(concat!("old_trim_ascii_", stringify!($name)), |b| {
+            b.iter(|| black_box(black_box($input.as_str()).trim_ascii().len()))
+        });
+
+        $c.bench_function(concat!("new_trim_ascii_", stringify!($name)), |b| {
+            b.iter(|| black_box(black_box($input.as_str()).trim_ascii_whitespace_2().len()))
+        });
+
+        $c.bench_function(
+            concat!("old_split_ascii_whitespace_", stringify!($name)),
+            |b| b.iter(|| black_box(black_box($input.as_str()).split_ascii_whitespace().count())),
+        );
+
+        $c.bench_function(
+            concat!("new_split_ascii_whitespace_", stringify!($name)),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        black_box($input.as_str())
+                            .split_ascii_whitespace_2()
+                            .count(),
+                    )
+                })
+            },
+        );
+    };
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // `padding` bytes of whitespace on each side of `len` bytes of words, which are
+    // `word_len` letters long on average.
+    let text = |padding: usize, len: usize, word_len: u32| -> String {
+        let mut rng = thread_rng();
+        let whitespace = [' ', '\t', '\n', '\r'];
+        let mut text: String = (0..padding)
+            .map(|_| whitespace[rng.gen_range(0..4)])
+            .collect();
+        text.extend((0..len).map(|_| {
+            if rng.gen_range(0..word_len) == 0 {
+                ' '
+            } else {
+                char::from(rng.gen_range(b'a'..=b'z'))
+            }
+        }));
+        text.extend((0..padding).map(|_| whitespace[rng.gen_range(0..4)]));
+        text
+    };
+
+    let short = text(4, 24, 6);
+    let short_padded = text(24, 24, 6);
+    let long = text(4, 4096, 6);
+    let long_padded = text(1024, 4096, 6);
+    let long_words = text(4, 4096, 64);
+
+    trim_benches!(short, c, short);
+    trim_benches!(short_padded, c, short_padded);
+    trim_benches!(long, c, long);
+    trim_benches!(long_padded, c, long_padded);
+    trim_benches!(long_words, c, long_words);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+// Passes the target on to `tests/c_program.rs`, which needs it to find a C compiler.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+}
@@ -0,0 +1,199 @@
+/* Generated by `tests/header.rs` from `EXPORTS`. Do not edit. */
+
+#ifndef IS_ASCII_SPEEDUPS_H
+#define IS_ASCII_SPEEDUPS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * For each class:
+ *
+ * - `is` returns whether `x` is in the class.
+ * - `all` returns whether every byte of `bytes[0..len]` is in the class.
+ * - `find_not` returns the index of the first byte of `bytes[0..len]` that is not
+ *   in the class, or `len` if there is none.
+ *
+ * `bytes` may be null when `len` is 0.
+ */
+
+/* `Ascii` */
+bool ias_is_ascii(uint8_t x);
+bool ias_all_ascii(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii(const uint8_t *bytes, size_t len);
+
+/* `AsciiAlphabetic` */
+bool ias_is_ascii_alphabetic(uint8_t x);
+bool ias_all_ascii_alphabetic(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_alphabetic(const uint8_t *bytes, size_t len);
+
+/* `AsciiAlphanumeric` */
+bool ias_is_ascii_alphanumeric(uint8_t x);
+bool ias_all_ascii_alphanumeric(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_alphanumeric(const uint8_t *bytes, size_t len);
+
+/* `AsciiControl` */
+bool ias_is_ascii_control(uint8_t x);
+bool ias_all_ascii_control(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_control(const uint8_t *bytes, size_t len);
+
+/* `AsciiDigit` */
+bool ias_is_ascii_digit(uint8_t x);
+bool ias_all_ascii_digit(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_digit(const uint8_t *bytes, size_t len);
+
+/* `AsciiGraphic` */
+bool ias_is_ascii_graphic(uint8_t x);
+bool ias_all_ascii_graphic(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_graphic(const uint8_t *bytes, size_t len);
+
+/* `AsciiHexdigit` */
+bool ias_is_ascii_hexdigit(uint8_t x);
+bool ias_all_ascii_hexdigit(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_hexdigit(const uint8_t *bytes, size_t len);
+
+/* `AsciiLowercase` */
+bool ias_is_ascii_lowercase(uint8_t x);
+bool ias_all_ascii_lowercase(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_lowercase(const uint8_t *bytes, size_t len);
+
+/* `AsciiPunctuation` */
+bool ias_is_ascii_punctuation(uint8_t x);
+bool ias_all_ascii_punctuation(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_punctuation(const uint8_t *bytes, size_t len);
+
+/* `AsciiUppercase` */
+bool ias_is_ascii_uppercase(uint8_t x);
+bool ias_all_ascii_uppercase(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_uppercase(const uint8_t *bytes, size_t len);
+
+/* `AsciiWhitespace` */
+bool ias_is_ascii_whitespace(uint8_t x);
+bool ias_all_ascii_whitespace(const uint8_t *bytes, size_t len);
+size_t ias_find_not_ascii_whitespace(const uint8_t *bytes, size_t len);
+
+/* `CSpace` */
+bool ias_is_c_space(uint8_t x);
+bool ias_all_c_space(const uint8_t *bytes, size_t len);
+size_t ias_find_not_c_space(const uint8_t *bytes, size_t len);
+
+/* `JsonWhitespace` */
+bool ias_is_json_whitespace(uint8_t x);
+bool ias_all_json_whitespace(const uint8_t *bytes, size_t len);
+size_t ias_find_not_json_whitespace(const uint8_t *bytes, size_t len);
+
+/* `XmlWhitespace` */
+bool ias_is_xml_whitespace(uint8_t x);
+bool ias_all_xml_whitespace(const uint8_t *bytes, size_t len);
+size_t ias_find_not_xml_whitespace(const uint8_t *bytes, size_t len);
+
+/* `Blank` */
+bool ias_is_blank(uint8_t x);
+bool ias_all_blank(const uint8_t *bytes, size_t len);
+size_t ias_find_not_blank(const uint8_t *bytes, size_t len);
+
+/* `UriUnreserved` */
+bool ias_is_uri_unreserved(uint8_t x);
+bool ias_all_uri_unreserved(const uint8_t *bytes, size_t len);
+size_t ias_find_not_uri_unreserved(const uint8_t *bytes, size_t len);
+
+/* `UriSubDelim` */
+bool ias_is_uri_sub_delim(uint8_t x);
+bool ias_all_uri_sub_delim(const uint8_t *bytes, size_t len);
+size_t ias_find_not_uri_sub_delim(const uint8_t *bytes, size_t len);
+
+/* `UriGenDelim` */
+bool ias_is_uri_gen_delim(uint8_t x);
+bool ias_all_uri_gen_delim(const uint8_t *bytes, size_t len);
+size_t ias_find_not_uri_gen_delim(const uint8_t *bytes, size_t len);
+
+/* `UriPchar` */
+bool ias_is_uri_pchar(uint8_t x);
+bool ias_all_uri_pchar(const uint8_t *bytes, size_t len);
+size_t ias_find_not_uri_pchar(const uint8_t *bytes, size_t len);
+
+/* `HttpTchar` */
+bool ias_is_http_tchar(uint8_t x);
+bool ias_all_http_tchar(const uint8_t *bytes, size_t len);
+size_t ias_find_not_http_tchar(const uint8_t *bytes, size_t len);
+
+/* `HttpFieldValueByte` */
+bool ias_is_http_field_value_byte(uint8_t x);
+bool ias_all_http_field_value_byte(const uint8_t *bytes, size_t len);
+size_t ias_find_not_http_field_value_byte(const uint8_t *bytes, size_t len);
+
+/* `RustIdentStart` */
+bool ias_is_rust_ident_start(uint8_t x);
+bool ias_all_rust_ident_start(const uint8_t *bytes, size_t len);
+size_t ias_find_not_rust_ident_start(const uint8_t *bytes, size_t len);
+
+/* `RustIdentContinue` */
+bool ias_is_rust_ident_continue(uint8_t x);
+bool ias_all_rust_ident_continue(const uint8_t *bytes, size_t len);
+size_t ias_find_not_rust_ident_continue(const uint8_t *bytes, size_t len);
+
+/* `CIdentStart` */
+bool ias_is_c_ident_start(uint8_t x);
+bool ias_all_c_ident_start(const uint8_t *bytes, size_t len);
+size_t ias_find_not_c_ident_start(const uint8_t *bytes, size_t len);
+
+/* `CIdentContinue` */
+bool ias_is_c_ident_continue(uint8_t x);
+bool ias_all_c_ident_continue(const uint8_t *bytes, size_t len);
+size_t ias_find_not_c_ident_continue(const uint8_t *bytes, size_t len);
+
+/* `JsIdentStart` */
+bool ias_is_js_ident_start(uint8_t x);
+bool ias_all_js_ident_start(const uint8_t *bytes, size_t len);
+size_t ias_find_not_js_ident_start(const uint8_t *bytes, size_t len);
+
+/* `JsIdentContinue` */
+bool ias_is_js_ident_continue(uint8_t x);
+bool ias_all_js_ident_continue(const uint8_t *bytes, size_t len);
+size_t ias_find_not_js_ident_continue(const uint8_t *bytes, size_t len);
+
+/* `SqlIdentStart` */
+bool ias_is_sql_ident_start(uint8_t x);
+bool ias_all_sql_ident_start(const uint8_t *bytes, size_t len);
+size_t ias_find_not_sql_ident_start(const uint8_t *bytes, size_t len);
+
+/* `SqlIdentContinue` */
+bool ias_is_sql_ident_continue(uint8_t x);
+bool ias_all_sql_ident_continue(const uint8_t *bytes, size_t len);
+size_t ias_find_not_sql_ident_continue(const uint8_t *bytes, size_t len);
+
+/* `Base64Standard` */
+bool ias_is_base64_standard(uint8_t x);
+bool ias_all_base64_standard(const uint8_t *bytes, size_t len);
+size_t ias_find_not_base64_standard(const uint8_t *bytes, size_t len);
+
+/* `Base64UrlSafe` */
+bool ias_is_base64_url_safe(uint8_t x);
+bool ias_all_base64_url_safe(const uint8_t *bytes, size_t len);
+size_t ias_find_not_base64_url_safe(const uint8_t *bytes, size_t len);
+
+/* `Base32Standard` */
+bool ias_is_base32_standard(uint8_t x);
+bool ias_all_base32_standard(const uint8_t *bytes, size_t len);
+size_t ias_find_not_base32_standard(const uint8_t *bytes, size_t len);
+
+/* `Base32Crockford` */
+bool ias_is_base32_crockford(uint8_t x);
+bool ias_all_base32_crockford(const uint8_t *bytes, size_t len);
+size_t ias_find_not_base32_crockford(const uint8_t *bytes, size_t len);
+
+/* `Base58` */
+bool ias_is_base58(uint8_t x);
+bool ias_all_base58(const uint8_t *bytes, size_t len);
+size_t ias_find_not_base58(const uint8_t *bytes, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
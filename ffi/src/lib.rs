@@ -0,0 +1,6 @@
+//! The C ABI exports of `is_ascii_speedups`, built as a `cdylib` and a
+//! `staticlib`. `include/is_ascii_speedups.h` declares them; it is generated from
+//! `EXPORTS` by `tests/header.rs`, which fails when it is out of date. Run that
+//! test with `UPDATE_HEADER=1` to rewrite it.
+
+pub use is_ascii_speedups::ffi::*;
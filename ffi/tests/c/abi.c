@@ -0,0 +1,41 @@
+/* Calls the exports through `include/is_ascii_speedups.h`, linked against the cdylib. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "is_ascii_speedups.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                         \
+    do {                                                                         \
+        if (!(condition)) {                                                      \
+            fprintf(stderr, "%s:%d: failed: %s\n", __FILE__, __LINE__, #condition); \
+            failures++;                                                          \
+        }                                                                        \
+    } while (0)
+
+int main(void) {
+    for (int x = 0; x <= 255; x++) {
+        CHECK(ias_is_ascii_digit((uint8_t)x) == (x >= '0' && x <= '9'));
+        CHECK(ias_is_c_space((uint8_t)x) == (x == ' ' || (x >= '\t' && x <= '\r')));
+    }
+    CHECK(ias_is_http_tchar('!'));
+    CHECK(!ias_is_http_tchar(':'));
+
+    const char *digits = "0123456789012345678";
+    CHECK(ias_all_ascii_digit((const uint8_t *)digits, strlen(digits)));
+    CHECK(ias_find_not_ascii_digit((const uint8_t *)digits, strlen(digits)) == strlen(digits));
+
+    const char *token = "Content-Type: text/plain";
+    CHECK(!ias_all_http_tchar((const uint8_t *)token, strlen(token)));
+    CHECK(ias_find_not_http_tchar((const uint8_t *)token, strlen(token)) == 12);
+
+    const char *base64 = "Zm9vYmFy Zm9v";
+    CHECK(ias_find_not_base64_standard((const uint8_t *)base64, strlen(base64)) == 8);
+
+    CHECK(ias_all_ascii_digit(NULL, 0));
+    CHECK(ias_find_not_ascii_digit(NULL, 0) == 0);
+
+    return failures != 0;
+}
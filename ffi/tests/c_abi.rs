@@ -0,0 +1,67 @@
+// Calls the exports through the signatures in `include/is_ascii_speedups.h`
+// rather than through their Rust declarations.
+
+use is_ascii_speedups::IsAscii2;
+use is_ascii_speedups_ffi as _;
+use std::ptr;
+
+extern "C" {
+    fn ias_is_ascii_digit(x: u8) -> bool;
+    fn ias_all_ascii_digit(bytes: *const u8, len: usize) -> bool;
+    fn ias_find_not_ascii_digit(bytes: *const u8, len: usize) -> usize;
+    fn ias_is_http_tchar(x: u8) -> bool;
+    fn ias_all_http_tchar(bytes: *const u8, len: usize) -> bool;
+    fn ias_find_not_http_tchar(bytes: *const u8, len: usize) -> usize;
+    fn ias_find_not_base64_standard(bytes: *const u8, len: usize) -> usize;
+    fn ias_is_c_space(x: u8) -> bool;
+}
+
+#[test]
+fn single_bytes() {
+    for x in 0..=u8::MAX {
+        assert!(
+            unsafe { ias_is_ascii_digit(x) } == x.is_ascii_digit_2(),
+            "Failed on {}",
+            x
+        );
+        assert!(
+            unsafe { ias_is_c_space(x) } == (x.is_ascii_whitespace_2() || x == b'\x0B'),
+            "Failed on {}",
+            x
+        );
+    }
+    assert!(unsafe { ias_is_http_tchar(b'!') });
+    assert!(!unsafe { ias_is_http_tchar(b':') });
+}
+
+#[test]
+fn buffers() {
+    let digits = b"0123456789012345678";
+    unsafe {
+        assert!(ias_all_ascii_digit(digits.as_ptr(), digits.len()));
+        assert_eq!(
+            ias_find_not_ascii_digit(digits.as_ptr(), digits.len()),
+            digits.len()
+        );
+        assert_eq!(ias_find_not_ascii_digit(b"12345678901a".as_ptr(), 12), 11);
+
+        let token = b"Content-Type: text/plain";
+        assert!(!ias_all_http_tchar(token.as_ptr(), token.len()));
+        assert_eq!(ias_find_not_http_tchar(token.as_ptr(), token.len()), 12);
+
+        let base64 = b"Zm9vYmFy Zm9v";
+        assert_eq!(
+            ias_find_not_base64_standard(base64.as_ptr(), base64.len()),
+            8
+        );
+    }
+}
+
+#[test]
+fn null_and_empty() {
+    unsafe {
+        assert!(ias_all_ascii_digit(ptr::null(), 0));
+        assert_eq!(ias_find_not_ascii_digit(ptr::null(), 0), 0);
+        assert!(ias_all_http_tchar(ptr::null(), 0));
+    }
+}
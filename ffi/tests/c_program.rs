@@ -0,0 +1,42 @@
+// Compiles `c/abi.c` against `include/is_ascii_speedups.h`, links it to the cdylib, and
+// runs it, so that the header and the exports are checked from C itself. The flags
+// are those of Unix compilers.
+#![cfg(unix)]
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn c_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // The cdylib is next to this test's executable, in `target/<profile>/deps`.
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_owned();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_abi");
+
+    let compiler = cc::Build::new()
+        .target(env!("TARGET"))
+        .host(env!("TARGET"))
+        .opt_level(0)
+        .get_compiler();
+    let status = compiler
+        .to_command()
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/abi.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lis_ascii_speedups_ffi")
+        .status()
+        .unwrap();
+    assert!(status.success(), "Failed to compile c/abi.c");
+
+    let status = Command::new(&program).status().unwrap();
+    assert!(status.success(), "c/abi.c failed its checks");
+}
@@ -0,0 +1,73 @@
+use is_ascii_speedups_ffi::EXPORTS;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+fn header() -> String {
+    let mut header = String::from(
+        "\
+/* Generated by `tests/header.rs` from `EXPORTS`. Do not edit. */
+
+#ifndef IS_ASCII_SPEEDUPS_H
+#define IS_ASCII_SPEEDUPS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+
+/*
+ * For each class:
+ *
+ * - `is` returns whether `x` is in the class.
+ * - `all` returns whether every byte of `bytes[0..len]` is in the class.
+ * - `find_not` returns the index of the first byte of `bytes[0..len]` that is not
+ *   in the class, or `len` if there is none.
+ *
+ * `bytes` may be null when `len` is 0.
+ */
+",
+    );
+    for export in EXPORTS {
+        write!(
+            header,
+            "
+/* `{}` */
+bool {}(uint8_t x);
+bool {}(const uint8_t *bytes, size_t len);
+size_t {}(const uint8_t *bytes, size_t len);
+",
+            export.class, export.is, export.all, export.find_not
+        )
+        .unwrap();
+    }
+    header.push_str(
+        "
+#ifdef __cplusplus
+}
+#endif
+
+#endif
+",
+    );
+    header
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/is_ascii_speedups.h");
+    let header = header();
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        fs::write(&path, header).unwrap();
+    } else {
+        let checked_in = fs::read_to_string(&path).unwrap();
+        assert!(
+            checked_in == header,
+            "{} is out of date. Rerun this test with UPDATE_HEADER=1.",
+            path.display()
+        );
+    }
+}
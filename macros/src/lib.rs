@@ -0,0 +1,343 @@
+//! The procedural macros of `is_ascii_speedups`, which re-exports them with its
+//! `macros` feature.
+//!
+//! The search for the cheapest encoding of a set, in `search.rs`, is shared with the
+//! `strip_search` tool, which prints the same code for pasting into the main crate by
+//! hand.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+mod search;
+
+use search::{ByteSet, Input};
+
+/// Defines zero-sized class types from POSIX bracket expressions.
+///
+/// ```
+/// use is_ascii_speedups::{ascii_class, AsciiClass};
+///
+/// ascii_class! {
+///     /// The bytes of a URL slug.
+///     pub Slug = [0-9a-zA-Z_-];
+///     Quote = "[\"'`]";
+/// }
+///
+/// assert!(Slug.contains_all(b"is-ascii_2"));
+/// assert!(Quote.contains_char('`'));
+/// assert!(!Quote.contains_u16(0x2019));
+/// ```
+///
+/// Each class implements `AsciiClass`, and has a `const fn contains_u16`. The
+/// membership tests use the cheapest encoding that fits the set, out of the ones
+/// hand-applied in `is_ascii_speedups`: a single range, a range after a case fold
+/// like `| 0b0010_0000`, a 64- or 128-bit shift mask, or one strip per chunk with
+/// an offset, as in `is_ascii_punctuation_2`. Sets that fit none of those use a
+/// 256-bit bitmap.
+///
+/// The sets follow POSIX: a `]` right after the opening `[` or `[^` is a byte, and
+/// so is a `-` at either end, there are no escapes, and `[:alpha:]` and the other
+/// classes of the `"C"` locale can be used inside, as can `[.x.]` and `[=x=]` for
+/// single characters. A negated set also contains the bytes from 128 up. This is
+/// the grammar of `ByteSet::parse`. Sets with quotes, backticks, backslashes or
+/// spaces, which aren't Rust tokens or are lost when tokenizing, can be written as
+/// string literals instead.
+#[proc_macro]
+pub fn ascii_class(input: TokenStream) -> TokenStream {
+    let expanded = match definitions(input) {
+        Ok(definitions) => definitions.iter().map(Definition::expand).collect(),
+        Err(message) => format!("::core::compile_error!({:?});", message),
+    };
+    expanded.parse().unwrap()
+}
+
+struct Definition {
+    attributes: String,
+    visibility: String,
+    name: String,
+    set: ByteSet,
+}
+
+fn definitions(input: TokenStream) -> Result<Vec<Definition>, String> {
+    let mut tokens = input.into_iter().peekable();
+    let mut definitions = Vec::new();
+    while tokens.peek().is_some() {
+        let mut attributes = String::new();
+        while matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+            tokens.next();
+            match tokens.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                    attributes.push_str(&format!("#{}\n", group));
+                }
+                _ => return Err("expected an attribute after `#`".into()),
+            }
+        }
+
+        let mut visibility = String::new();
+        if matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == "pub") {
+            visibility.push_str("pub");
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    visibility.push_str(&group.to_string());
+                    tokens.next();
+                }
+            }
+        }
+
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            _ => return Err("expected the name of a class".into()),
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {}
+            _ => return Err(format!("expected `=` after `{}`", name)),
+        }
+        let spec = match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                format!("[{}]", untokenize(group.stream()))
+            }
+            Some(TokenTree::Literal(literal)) => string_literal(&literal.to_string())?,
+            _ => return Err(format!("expected a bracket expression after `{} =`", name)),
+        };
+        let set =
+            search::parse_bracket(&spec).map_err(|error| format!("in `{}`: {}", name, error))?;
+
+        match tokens.next() {
+            None => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => {}
+            _ => return Err(format!("expected `;` after the set of `{}`", name)),
+        }
+        definitions.push(Definition {
+            attributes,
+            visibility,
+            name,
+            set,
+        });
+    }
+    Ok(definitions)
+}
+
+/// Turns the tokens of a bracket expression back into its text. Whitespace between
+/// tokens is lost, which is why spaces need a string literal.
+fn untokenize(stream: TokenStream) -> String {
+    let mut text = String::new();
+    for token in stream {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                text.push_str(open);
+                text.push_str(&untokenize(group.stream()));
+                text.push_str(close);
+            }
+            token => text.push_str(&token.to_string()),
+        }
+    }
+    text
+}
+
+/// Returns the value of a string literal, given as written.
+fn string_literal(literal: &str) -> Result<String, String> {
+    let not_a_string = || format!("expected a string literal, not `{}`", literal);
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw[hashes..raw.len() - hashes]
+            .strip_prefix('"')
+            .and_then(|raw| raw.strip_suffix('"'))
+            .map(str::to_string)
+            .ok_or_else(not_a_string);
+    }
+    let escaped = literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .ok_or_else(not_a_string)?;
+
+    let mut value = String::new();
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                value.push(u8::from_str_radix(&hex, 16).map_err(|_| not_a_string())? as char);
+            }
+            // A line continuation skips the whitespace at the start of the next line.
+            Some('\n') => {
+                let rest = chars.as_str().trim_start();
+                chars = rest.chars();
+            }
+            Some(c @ ('\\' | '"' | '\'')) => value.push(c),
+            // `\u{...}` is never ASCII, so it's rejected by the bracket parser anyway.
+            _ => return Err("bracket expressions must be ASCII".into()),
+        }
+    }
+    Ok(value)
+}
+
+/// The fallback for sets that fit no other technique: one bit per byte, in two
+/// `u128`s.
+fn table_body(set: &ByteSet, input: Input, input_expr: &str) -> String {
+    let half =
+        |start: usize| (0..128).fold(0u128, |bits, i| bits | ((set[start + i] as u128) << i));
+    let test = "(TABLE[x as usize >> 7] >> (x & 127)) & 1 != 0";
+    match input {
+        Input::U8 => format!(
+            "const TABLE: [u128; 2] = [{}, {}];\nlet x = {};\n{}",
+            half(0),
+            half(128),
+            input_expr,
+            test
+        ),
+        Input::Char => format!(
+            "const TABLE: [u128; 2] = [{}, {}];\nlet x = {} as u32;\nx < 256 && {}",
+            half(0),
+            half(128),
+            input_expr,
+            test
+        ),
+    }
+}
+
+impl Definition {
+    fn expand(&self) -> String {
+        let body = |input, input_expr| match search::search(&self.set).first() {
+            Some(encoding) => encoding.standalone_body(&self.set, input, input_expr),
+            None => table_body(&self.set, input, input_expr),
+        };
+        let Definition {
+            attributes,
+            visibility,
+            name,
+            ..
+        } = self;
+        format!(
+            "{attributes}\
+             #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]\n\
+             {visibility} struct {name};\n\
+             \n\
+             impl {name} {{\n\
+             /// Returns `true` iff `codepoint` is in this class. Codepoints above 255\n\
+             /// are never in a class.\n\
+             #[inline]\n\
+             pub const fn contains_u16(&self, codepoint: u16) -> bool {{\n\
+             {u16_body}\n\
+             }}\n\
+             }}\n\
+             \n\
+             impl ::is_ascii_speedups::AsciiClass for {name} {{\n\
+             #[inline]\n\
+             fn contains(&self, byte: u8) -> bool {{\n\
+             {u8_body}\n\
+             }}\n\
+             \n\
+             #[inline]\n\
+             fn contains_char(&self, c: char) -> bool {{\n\
+             {char_body}\n\
+             }}\n\
+             }}\n",
+            u16_body = body(Input::Char, "codepoint"),
+            u8_body = body(Input::U8, "byte"),
+            char_body = body(Input::Char, "c"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(string_literal(r#""[\"'`]""#).unwrap(), "[\"'`]");
+        assert_eq!(string_literal(r#""[ \t\\\x7F]""#).unwrap(), "[ \t\\\x7F]");
+        assert_eq!(string_literal(r###"r#"[ "]"#"###).unwrap(), "[ \"]");
+        assert_eq!(string_literal("\"[a\\\n    b]\"").unwrap(), "[ab]");
+        assert!(string_literal("'a'").is_err());
+        assert!(string_literal(r#""\u{e9}""#).is_err());
+    }
+
+    // `ascii_class!` and `ByteSet::parse` agree on every expression made of up to
+    // four of these pieces, which cover the corners of the grammar.
+    #[test]
+    fn same_grammar_as_byte_set() {
+        use is_ascii_speedups::bracket::ByteSet;
+        use is_ascii_speedups::AsciiClass;
+
+        const PIECES: [&str; 16] = [
+            "]",
+            "^",
+            "-",
+            "[",
+            "a",
+            "z",
+            "0",
+            ":",
+            ".",
+            "=",
+            "[:alpha:]",
+            "[:space:]",
+            "[:word:]",
+            "[.-.]",
+            "[=a=]",
+            "[.ab.]",
+        ];
+        let mut expressions = vec![String::new()];
+        for _ in 0..4 {
+            let longer: Vec<String> = expressions
+                .iter()
+                .flat_map(|expression| PIECES.iter().map(move |piece| expression.clone() + piece))
+                .collect();
+            expressions.extend(longer);
+        }
+        expressions.sort();
+        expressions.dedup();
+        // Each file has its own table of the named classes.
+        for class in [
+            "alnum", "alpha", "blank", "cntrl", "digit", "graph", "lower", "print", "punct",
+            "space", "upper", "xdigit",
+        ] {
+            expressions.push(format!("[:{}:]]", class));
+        }
+
+        for expression in expressions {
+            for spec in [format!("[{}", expression), format!("[^{}", expression)] {
+                match (search::parse_bracket(&spec), ByteSet::parse(&spec)) {
+                    (Ok(set), Ok(byte_set)) => {
+                        for x in 0..=u8::MAX {
+                            assert_eq!(set[x as usize], byte_set.contains(x), "{} on {}", spec, x);
+                        }
+                    }
+                    (Err(_), Err(_)) => {}
+                    (set, byte_set) => panic!("{}: {:?} and {:?}", spec, set.is_ok(), byte_set),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_fallback() {
+        // Too spread out for a bitmap, and too close together for strips.
+        let mut set = [false; 256];
+        for x in [0, 2, 0x80, 0xFE] {
+            set[x] = true;
+        }
+        assert!(search::search(&set).is_empty());
+        let body = table_body(&set, Input::U8, "byte");
+        assert!(
+            body.contains("[5, 85070591730234615865843651857942052865]"),
+            "{}",
+            body
+        );
+    }
+}
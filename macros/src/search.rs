@@ -0,0 +1,847 @@
+//! The search behind `ascii_class!` and `strip_search`: finding the cheapest branchless
+//! encoding of a set of bytes, and writing it out as Rust.
+//!
+//! This file only uses `std`, so `strip_search` includes it with `#[path]`. Some of it
+//! is only used by one of the two.
+
+use std::fmt::Write;
+
+/// A set of bytes, indexed by byte.
+pub type ByteSet = [bool; 256];
+
+/// Parses a set written like the inside of a regex bracket expression: bytes and `a-z`
+/// ranges, with `\xHH`, `\t`, `\n`, `\r`, `\0` and `\` escapes. A `-` at either end is
+/// a byte, not a range.
+#[allow(dead_code)] // Only for `strip_search`.
+pub fn parse_set(spec: &str) -> Result<ByteSet, String> {
+    // `None` is an unescaped `-`.
+    let mut tokens = Vec::new();
+    let mut chars = spec.chars();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\\' => Some(match chars.next() {
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    u8::from_str_radix(&hex, 16)
+                        .map_err(|_| format!("`\\x{}` isn't a byte", hex))?
+                }
+                Some('t') => b'\t',
+                Some('n') => b'\n',
+                Some('r') => b'\r',
+                Some('0') => b'\0',
+                Some(c) if c.is_ascii() => c as u8,
+                Some(c) => return Err(format!("`\\{}` isn't an escape", c)),
+                None => return Err("the set ends with a `\\`".into()),
+            }),
+            '-' => None,
+            c if c.is_ascii() => Some(c as u8),
+            c => {
+                return Err(format!(
+                    "`{}` isn't ASCII; write bytes from 128 up as `\\x` escapes",
+                    c
+                ))
+            }
+        };
+        tokens.push(token);
+    }
+
+    let mut set = [false; 256];
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i..] {
+            [Some(first), None, Some(last), ..] => {
+                if first > last {
+                    return Err(format!("the range `{}-{}` is backwards", first, last));
+                }
+                set[first as usize..=last as usize].fill(true);
+                i += 3;
+            }
+            [token, ..] => {
+                set[token.unwrap_or(b'-') as usize] = true;
+                i += 1;
+            }
+            [] => unreachable!(),
+        }
+    }
+    Ok(set)
+}
+
+type Predicate = fn(&u8) -> bool;
+
+/// The POSIX character classes that can appear in a bracket expression, with the
+/// semantics of the `"C"` locale.
+const POSIX_CLASSES: [(&str, Predicate); 12] = [
+    ("alnum", u8::is_ascii_alphanumeric),
+    ("alpha", u8::is_ascii_alphabetic),
+    ("blank", |&x| x == b' ' || x == b'\t'),
+    ("cntrl", u8::is_ascii_control),
+    ("digit", u8::is_ascii_digit),
+    ("graph", u8::is_ascii_graphic),
+    ("lower", u8::is_ascii_lowercase),
+    ("print", |&x| x.is_ascii_graphic() || x == b' '),
+    ("punct", u8::is_ascii_punctuation),
+    // Unlike `is_ascii_whitespace`, this includes vertical tab.
+    ("space", |&x| x.is_ascii_whitespace() || x == b'\x0B'),
+    ("upper", u8::is_ascii_uppercase),
+    ("xdigit", u8::is_ascii_hexdigit),
+];
+
+/// Parses a POSIX bracket expression like `[0-9a-zA-Z_-]` or `[^[:space:]"]`. As in
+/// POSIX, a `]` right after the opening `[` or `[^` is a byte, and so is a `-` at
+/// either end, and there are no escapes. A negated expression matches every byte that
+/// isn't listed, including the bytes from 128 up. Collating symbols and equivalence
+/// classes like `[.-.]` and `[=a=]` can only name a single character, as in the `"C"`
+/// locale.
+///
+/// This is the grammar of `is_ascii_speedups::bracket::ByteSet::parse`, so that a set
+/// means the same in `ascii_class!` as at runtime. The tests of the macros check that
+/// the two agree.
+pub fn parse_bracket(spec: &str) -> Result<ByteSet, String> {
+    if !spec.is_ascii() {
+        return Err("bracket expressions must be ASCII".into());
+    }
+    let bytes = spec.as_bytes();
+    if bytes.first() != Some(&b'[') {
+        return Err("bracket expressions start with `[`".into());
+    }
+    let negated = bytes.get(1) == Some(&b'^');
+    let first = 1 + negated as usize;
+
+    let mut set = [false; 256];
+    let mut i = first;
+    loop {
+        match &bytes[i..] {
+            [] => return Err("the bracket expression has no closing `]`".into()),
+            [b']', ..] if i != first => break,
+            [b'[', b':', after @ ..] => {
+                let end = after
+                    .windows(2)
+                    .position(|window| window == b":]")
+                    .ok_or("a `[:` has no closing `:]`")?;
+                let name = std::str::from_utf8(&after[..end]).unwrap();
+                let (_, is_member) = POSIX_CLASSES
+                    .iter()
+                    .find(|(class, _)| *class == name)
+                    .ok_or_else(|| format!("`[:{}:]` isn't a POSIX class", name))?;
+                for x in 0..=255u8 {
+                    set[x as usize] |= is_member(&x);
+                }
+                i += 2 + end + 2;
+            }
+            // An equivalence class can't start a range.
+            [b'[', b'=', ..] => {
+                let (x, after) = element(bytes, i)?;
+                set[x as usize] = true;
+                i = after;
+            }
+            _ => {
+                let (start, after) = element(bytes, i)?;
+                // A `-` right before the closing `]` is a byte, not a range.
+                match &bytes[after..] {
+                    [b'-', end, ..] if *end != b']' => {
+                        let (end, after) = element(bytes, after + 1)?;
+                        if start > end {
+                            return Err(format!(
+                                "the range `{}-{}` is backwards",
+                                start as char, end as char
+                            ));
+                        }
+                        set[start as usize..=end as usize].fill(true);
+                        i = after;
+                    }
+                    _ => {
+                        set[start as usize] = true;
+                        i = after;
+                    }
+                }
+            }
+        }
+    }
+    if i + 1 != bytes.len() {
+        return Err("there's more after the closing `]`".into());
+    }
+
+    if negated {
+        set.iter_mut().for_each(|member| *member = !*member);
+    }
+    Ok(set)
+}
+
+/// Parses the byte, `[.x.]` or `[=x=]` at `index`, returning the byte and the index
+/// after it.
+fn element(bytes: &[u8], index: usize) -> Result<(u8, usize), String> {
+    match bytes[index..] {
+        [b'[', delimiter @ (b'.' | b'='), ..] => match bytes[index + 2..] {
+            [x, end, b']', ..] if end == delimiter => Ok((x, index + 5)),
+            _ => Err(format!(
+                "a `[{}` must be followed by one character and `{}]`",
+                delimiter as char, delimiter as char
+            )),
+        },
+        _ => Ok((bytes[index], index + 1)),
+    }
+}
+
+/// How an encoding tests the masked and offset input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Technique {
+    /// Always false.
+    Empty,
+    /// `x.wrapping_sub(start) < len`. A `len` of 256 is always true.
+    Range { start: u8, len: u16 },
+    /// Bit `x - start` of `bits`, for `x` in `start..start + len`. `len` is at most 128.
+    Bitmap { start: u8, len: u8, bits: u128 },
+    /// `handle_strip_of_each_chunk!`, with `256 / chunk_len` chunks of `chunk_len`
+    /// bytes, each holding at most one strip. `starts` and `lens` are the macro's
+    /// tables, and are 0 for chunks with no strip.
+    Strips {
+        chunk_len: usize,
+        starts: Vec<u8>,
+        lens: Vec<u8>,
+    },
+}
+
+/// A branchless encoding of a set of bytes: `x` is in the set iff
+/// `(x | mask).wrapping_add(offset)` passes `technique`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    pub mask: u8,
+    pub offset: u8,
+    pub technique: Technique,
+}
+
+/// The chunk sizes the strip search tries, from the smallest tables up.
+pub const CHUNK_LENS: [usize; 4] = [32, 16, 8, 4];
+
+impl Encoding {
+    /// Evaluates the encoding, to check it against the set it was found for.
+    #[allow(dead_code)] // Only for `strip_search`.
+    pub fn contains(&self, x: u8) -> bool {
+        let x = (x | self.mask).wrapping_add(self.offset);
+        match &self.technique {
+            Technique::Empty => false,
+            Technique::Range { start, len } => (x.wrapping_sub(*start) as u16) < *len,
+            Technique::Bitmap { start, len, bits } => {
+                let x = x.wrapping_sub(*start);
+                x < *len && (bits >> x) & 1 != 0
+            }
+            Technique::Strips {
+                chunk_len,
+                starts,
+                lens,
+            } => {
+                let chunk_number = x as usize / chunk_len;
+                x.wrapping_sub(starts[chunk_number]) < lens[chunk_number]
+            }
+        }
+    }
+
+    /// A one-line description, for the search report.
+    #[allow(dead_code)] // Only for `strip_search`.
+    pub fn describe(&self) -> String {
+        let mut description = match &self.technique {
+            Technique::Empty => "empty set".to_string(),
+            Technique::Range { start, len } => format!("range of {} from {}", len, start),
+            Technique::Bitmap { start, len, .. } => format!(
+                "u{} bitmap of {} from {}",
+                if *len <= 64 { 64 } else { 128 },
+                len,
+                start
+            ),
+            Technique::Strips { chunk_len, .. } => {
+                format!("one strip per {}-byte chunk", chunk_len)
+            }
+        };
+        if self.mask != 0 {
+            write!(description, ", after `| {}`", mask_literal(self.mask)).unwrap();
+        }
+        if self.offset != 0 {
+            write!(description, ", after `+ {}`", self.offset).unwrap();
+        }
+        description
+    }
+}
+
+/// Returns `{x | mask : x in set}`, if `x` is in `set` iff `x | mask` is in it, so that
+/// testing `x | mask` against it tests `x` against `set`.
+fn fold(set: &ByteSet, mask: u8) -> Option<ByteSet> {
+    let mut folded = [false; 256];
+    for x in 0..=255u8 {
+        folded[(x | mask) as usize] |= set[x as usize];
+    }
+    (0..=255u8)
+        .all(|x| set[x as usize] == folded[(x | mask) as usize])
+        .then_some(folded)
+}
+
+/// The maximal runs of members of `set`, as inclusive ranges.
+fn ranges(set: &ByteSet) -> Vec<(u8, u8)> {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for x in (0..=255).filter(|&x| set[x as usize]) {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == x => *last = x,
+            _ => ranges.push((x, x)),
+        }
+    }
+    ranges
+}
+
+/// The smallest and largest members of `set`.
+fn bounds(set: &ByteSet) -> Option<(u8, u8)> {
+    let first = set.iter().position(|&member| member)?;
+    let last = set.iter().rposition(|&member| member)?;
+    Some((first as u8, last as u8))
+}
+
+fn range(set: &ByteSet) -> Option<Technique> {
+    let Some((first, last)) = bounds(set) else {
+        return Some(Technique::Empty);
+    };
+    set[first as usize..=last as usize]
+        .iter()
+        .all(|&member| member)
+        .then_some(Technique::Range {
+            start: first,
+            len: (last - first) as u16 + 1,
+        })
+}
+
+fn bitmap(set: &ByteSet, max_len: u8) -> Option<Technique> {
+    let (first, last) = bounds(set)?;
+    // Starting at 0 saves the subtraction.
+    let start = if last < max_len { 0 } else { first };
+    let len = (last - start) as usize + 1;
+    (len <= max_len as usize).then(|| Technique::Bitmap {
+        start,
+        len: len as u8,
+        bits: (start..=last).fold(0, |bits, x| {
+            bits | ((set[x as usize] as u128) << (x - start))
+        }),
+    })
+}
+
+/// Searches for the smallest offset that puts at most one strip of `set` in each
+/// `chunk_len`-byte chunk. Members aren't allowed to wrap past 255, since a `char`
+/// doesn't wrap there.
+fn strips(set: &ByteSet, chunk_len: usize) -> Option<(u8, Technique)> {
+    let (_, last) = bounds(set)?;
+    'offsets: for offset in 0..=255 - last {
+        let is_member = |y: usize| y >= offset as usize && set[y - offset as usize];
+        let mut starts = vec![0; 256 / chunk_len];
+        let mut lens = vec![0; 256 / chunk_len];
+        for (chunk_number, chunk) in (0..256).step_by(chunk_len).enumerate() {
+            let chunk = chunk..chunk + chunk_len;
+            let Some(first) = chunk.clone().find(|&y| is_member(y)) else {
+                continue;
+            };
+            let len = chunk
+                .clone()
+                .skip_while(|&y| y < first)
+                .take_while(|&y| is_member(y))
+                .count();
+            if chunk.clone().skip(first - chunk.start + len).any(is_member) {
+                continue 'offsets;
+            }
+            starts[chunk_number] = first as u8;
+            lens[chunk_number] = len as u8;
+        }
+        return Some((
+            offset,
+            Technique::Strips {
+                chunk_len,
+                starts,
+                lens,
+            },
+        ));
+    }
+    None
+}
+
+/// Searches for one technique, returning the offset it needs along with it.
+type TechniqueSearch = Box<dyn Fn(&ByteSet) -> Option<(u8, Technique)>>;
+
+/// Returns every technique that can encode `set`, cheapest first, each with the
+/// first OR-mask that works with it. Masks with fewer bits are tried first.
+pub fn search(set: &ByteSet) -> Vec<Encoding> {
+    let mut masks: Vec<u8> = (0..=255).collect();
+    masks.sort_by_key(|mask| mask.count_ones());
+    let folds: Vec<(u8, ByteSet)> = masks
+        .into_iter()
+        .filter_map(|mask| Some((mask, fold(set, mask)?)))
+        .collect();
+
+    let mut searches: Vec<TechniqueSearch> = vec![
+        Box::new(|set| Some((0, range(set)?))),
+        Box::new(|set| Some((0, bitmap(set, 64)?))),
+    ];
+    for chunk_len in CHUNK_LENS {
+        searches.push(Box::new(move |set| strips(set, chunk_len)));
+    }
+    searches.push(Box::new(|set| Some((0, bitmap(set, 128)?))));
+
+    let mut encodings = Vec::new();
+    for search in searches {
+        let found = folds.iter().find_map(|(mask, folded)| {
+            let (offset, technique) = search(folded)?;
+            Some(Encoding {
+                mask: *mask,
+                offset,
+                technique,
+            })
+        });
+        encodings.extend(found);
+        if let Some(Encoding {
+            technique: Technique::Empty | Technique::Range { .. },
+            ..
+        }) = encodings.last()
+        {
+            // Nothing beats a single comparison.
+            break;
+        }
+    }
+    encodings
+}
+
+/// The type of the input of the generated code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    U8,
+    /// A `char`, or any other type that's tested as a `u32` after an `as` cast, like
+    /// `u16`.
+    Char,
+}
+
+/// Writes `x` as a literal of the working type of `input`: a byte literal for ASCII
+/// `u8`s, `'x' as u32` for ASCII `char`s, and a number otherwise.
+fn literal(x: u8, input: Input) -> String {
+    let escaped = x.escape_ascii().to_string();
+    match input {
+        _ if x >= 128 => x.to_string(),
+        Input::U8 => format!("b'{}'", escaped),
+        Input::Char => format!("'{}' as u32", escaped),
+    }
+}
+
+/// Writes `y`, a value after the offset, in terms of the byte it came from.
+fn offset_literal(y: u8, offset: u8) -> String {
+    if offset == 0 {
+        literal(y, Input::U8)
+    } else if y == offset {
+        offset.to_string()
+    } else {
+        format!("{} + {}", literal(y - offset, Input::U8), offset)
+    }
+}
+
+/// Writes `mask` like the `0b0010_0000` of `is_ascii_alphabetic_2`.
+fn mask_literal(mask: u8) -> String {
+    format!("0b{:04b}_{:04b}", mask >> 4, mask & 0xF)
+}
+
+/// Writes a binary literal with the bits grouped by four, like the bitmap of
+/// `is_ascii_whitespace_2`.
+fn binary(bits: u128, len: u8, width: u32) -> String {
+    let digits = format!("{:b}", bits);
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i) % 4 == 0 {
+            grouped.push('_');
+        }
+        grouped.push(digit);
+    }
+    debug_assert!(digits.len() <= len as usize);
+    format!("0b{}_u{}", grouped, width)
+}
+
+impl Encoding {
+    /// Returns the statements of a function body that tests `input_expr`, which has
+    /// the type `input`, against `set`, which the encoding was found for.
+    #[allow(dead_code)] // Only for `strip_search`.
+    pub fn body(&self, set: &ByteSet, input: Input, input_expr: &str) -> String {
+        self.body_with(set, input, input_expr, false)
+    }
+
+    /// Like `body`, but without `handle_strip_of_each_chunk!`, for code outside of
+    /// this crate. The result also works in a `const fn`.
+    #[allow(dead_code)] // Only for `ascii_class!`.
+    pub fn standalone_body(&self, set: &ByteSet, input: Input, input_expr: &str) -> String {
+        self.body_with(set, input, input_expr, true)
+    }
+
+    fn body_with(&self, set: &ByteSet, input: Input, input_expr: &str, standalone: bool) -> String {
+        let ty = match input {
+            Input::U8 => "u8",
+            Input::Char => "u32",
+        };
+        // Method calls auto-deref, so `*self` can be called on as `self`.
+        let receiver = |x: &str| match x.strip_prefix('*') {
+            Some(x) => x.to_string(),
+            None => x.to_string(),
+        };
+        let mut x = match input {
+            Input::U8 => input_expr.to_string(),
+            Input::Char => format!("({} as u32)", input_expr),
+        };
+        if self.mask != 0 {
+            x = format!("({} | {})", x, mask_literal(self.mask));
+        }
+        if self.offset != 0 {
+            x = format!("{}.wrapping_add({})", receiver(&x), self.offset);
+        }
+        // `let x = (*self as u32);` doesn't need the parentheses.
+        let bare_x = match input {
+            Input::Char if self.mask == 0 && self.offset == 0 => format!("{} as u32", input_expr),
+            _ => x.clone(),
+        };
+        let mut body = String::new();
+
+        match &self.technique {
+            Technique::Empty | Technique::Range { len: 256, .. } if standalone => {
+                // Keep the input used, since this isn't pasted by hand.
+                writeln!(body, "let _ = {};", input_expr).unwrap();
+            }
+            _ => {}
+        }
+        match &self.technique {
+            Technique::Empty => body.push_str("false\n"),
+            // Every `u8`, but not every `char`.
+            Technique::Range { len: 256, .. } if input == Input::U8 => body.push_str("true\n"),
+            Technique::Range { start: 0, len } => {
+                writeln!(body, "let x = {};", bare_x).unwrap();
+                writeln!(body, "x < {}", len).unwrap();
+            }
+            Technique::Range { start, len } => {
+                writeln!(
+                    body,
+                    "let x = {}.wrapping_sub({});",
+                    receiver(&x),
+                    literal(*start, input)
+                )
+                .unwrap();
+                writeln!(body, "x < {}", len).unwrap();
+            }
+            Technique::Bitmap { start, len, bits } => {
+                let width = if *len <= 64 { 64 } else { 128 };
+                body.push_str(&format!(
+                    "// Bit `i` of the long binary number, counting from 0 on the right, is 1 iff\n\
+                     // {} is in the set.\n",
+                    if *start == 0 {
+                        "`i`".to_string()
+                    } else {
+                        format!("`i` plus {}", literal(*start, Input::U8))
+                    }
+                ));
+                if *start == 0 {
+                    writeln!(body, "let x = {};", bare_x).unwrap();
+                    writeln!(
+                        body,
+                        "x <= {} && (({} >> x) & 1) != 0",
+                        literal(len - 1, input),
+                        binary(*bits, *len, width)
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        body,
+                        "let x = {}.wrapping_sub({});",
+                        receiver(&x),
+                        literal(*start, input)
+                    )
+                    .unwrap();
+                    writeln!(
+                        body,
+                        "x < {} && (({} >> x) & 1) != 0",
+                        len,
+                        binary(*bits, *len, width)
+                    )
+                    .unwrap();
+                }
+            }
+            Technique::Strips {
+                chunk_len,
+                starts,
+                lens,
+            } => {
+                if self.offset != 0 {
+                    writeln!(
+                        body,
+                        "// Add {} to the codepoint so that each strip of consecutive matching codepoints\n\
+                         // is in a separate {}-codepoint chunk.",
+                        self.offset, chunk_len
+                    )
+                    .unwrap();
+                }
+                writeln!(body, "let x = {};", bare_x).unwrap();
+                let starts = starts
+                    .iter()
+                    .zip(lens)
+                    .map(|(&start, &len)| match len {
+                        0 => "0".to_string(),
+                        _ => offset_literal(start, self.offset),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let lens = lens
+                    .iter()
+                    .map(|len| len.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if standalone {
+                    let chunks = 256 / chunk_len;
+                    let shift = chunk_len.trailing_zeros();
+                    // The casts to and from `u8` are only needed for `char`s.
+                    let (to_u8, from_u8) = match input {
+                        Input::U8 => ("", String::new()),
+                        Input::Char => (" as u8", format!(" as {}", ty)),
+                    };
+                    writeln!(
+                        body,
+                        "const STARTING_CODEPOINTS: [u8; {chunks}] = [{starts}];\n\
+                         const STRIP_LENGTHS: [u8; {chunks}] = [{lens}];\n\
+                         let chunk_number = (x{to_u8} >> {shift}) as usize;\n\
+                         x.wrapping_sub(STARTING_CODEPOINTS[chunk_number]{from_u8}) \
+                         < STRIP_LENGTHS[chunk_number]{from_u8}",
+                    )
+                    .unwrap();
+                    return body;
+                }
+                let mut args = vec!["x".to_string(), ty.to_string()];
+                // 32 is the macro's default.
+                if *chunk_len != 32 {
+                    args.push(chunk_len.to_string());
+                }
+                args.push(format!("[{}]", starts));
+                args.push(format!("[{}]", lens));
+                if self.mask != 0 {
+                    args.push(format!("mask: {}", mask_literal(self.mask)));
+                }
+                if self.offset != 0 {
+                    args.push(format!("offset: {}", self.offset));
+                }
+                let ranges = ranges(set)
+                    .into_iter()
+                    .map(|(first, last)| {
+                        format!(
+                            "({}, {})",
+                            literal(first, Input::U8),
+                            literal(last, Input::U8)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                args.push(format!("set: [{}]", ranges));
+                body.push_str("handle_strip_of_each_chunk!(\n");
+                for arg in args {
+                    writeln!(body, "    {},", arg).unwrap();
+                }
+                body.push_str(")\n");
+            }
+        }
+        body
+    }
+
+    /// Returns `u8` and `char` methods named `name` that test `set`, in the style of
+    /// `IsAscii2`.
+    #[allow(dead_code)] // Only for `strip_search`.
+    pub fn methods(&self, set: &ByteSet, name: &str) -> String {
+        let mut methods = String::new();
+        for (input, ty) in [(Input::U8, "u8"), (Input::Char, "char")] {
+            writeln!(
+                methods,
+                "// impl ... for {}\n#[must_use]\n#[inline]\nfn {}(&self) -> bool {{",
+                ty, name
+            )
+            .unwrap();
+            for line in self.body(set, input, "*self").lines() {
+                writeln!(methods, "    {}", line).unwrap();
+            }
+            methods.push_str("}\n\n");
+        }
+        methods
+    }
+}
+
+/// Returns exhaustive `u8` and `char` tests of the methods named `name` against `set`,
+/// in the style of the tests of `IsAscii2`.
+#[allow(dead_code)] // Only for `strip_search`.
+pub fn tests(set: &ByteSet, name: &str) -> String {
+    let members: String = (0..=255u8)
+        .filter(|&x| set[x as usize])
+        .map(|x| x.escape_ascii().to_string())
+        .collect();
+    let test_name = name.trim_start_matches("is_").trim_end_matches("_2");
+    format!(
+        "const SET: &[u8] = b\"{members}\";\n\
+         \n\
+         #[test]\n\
+         fn {test_name}_u8() {{\n\
+         \x20   for x in 0..=u8::MAX {{\n\
+         \x20       assert!(SET.contains(&x) == x.{name}(), \"Failed on {{}}\", x);\n\
+         \x20   }}\n\
+         }}\n\
+         \n\
+         #[test]\n\
+         fn {test_name}_char() {{\n\
+         \x20   for x in ('\\0'..='\\u{{d7ff}}').chain('\\u{{e000}}'..='\\u{{10ffff}}') {{\n\
+         \x20       let expected = u8::try_from(x).is_ok_and(|x| SET.contains(&x));\n\
+         \x20       assert!(expected == x.{name}(), \"Failed on {{}}\", x);\n\
+         \x20   }}\n\
+         }}\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_of(f: impl Fn(u8) -> bool) -> ByteSet {
+        core::array::from_fn(|x| f(x as u8))
+    }
+
+    fn check(set: &ByteSet) {
+        for encoding in search(set) {
+            for x in 0..=255 {
+                assert_eq!(
+                    encoding.contains(x),
+                    set[x as usize],
+                    "{:?} failed on {}",
+                    encoding,
+                    x
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let set = parse_set(r"a-c\x80-\x81\--").unwrap();
+        let members: Vec<u8> = (0..=255).filter(|&x| set[x as usize]).collect();
+        assert_eq!(members, [b'-', b'a', b'b', b'c', 0x80, 0x81]);
+        assert!(parse_set("z-a").is_err());
+        assert!(parse_set(r"\xZZ").is_err());
+        assert!(parse_set("\u{e9}").is_err());
+    }
+
+    #[test]
+    fn parse_brackets() {
+        let members = |spec| {
+            let set = parse_bracket(spec).unwrap();
+            (0..=255).filter(|&x| set[x as usize]).collect::<Vec<u8>>()
+        };
+        assert_eq!(members("[0-2a_-]"), b"-012_a");
+        assert_eq!(members("[]a-]"), b"-]a");
+        assert_eq!(members("[^]]").len(), 255);
+        assert_eq!(members("[[:digit:]x]"), b"0123456789x");
+        assert_eq!(members("[[:blank:]]"), b"\t ");
+        assert_eq!(members("[^[:print:][:cntrl:]]").len(), 128);
+        assert_eq!(members(r"[\n]"), b"\\n");
+        assert!(parse_bracket("abc").is_err());
+        assert!(parse_bracket("[abc").is_err());
+        assert!(parse_bracket("[]").is_err());
+        assert!(parse_bracket("[a]b").is_err());
+        assert!(parse_bracket("[z-a]").is_err());
+        assert!(parse_bracket("[[:word:]]").is_err());
+        assert_eq!(members("[[=a=][.-.]-/]"), b"-./a");
+        assert_eq!(members("[[.^.]]"), b"^");
+        assert!(parse_bracket("[[.ab.]]").is_err());
+        assert!(parse_bracket("[[=a.]]").is_err());
+    }
+
+    #[test]
+    fn finds_the_existing_encodings() {
+        let digit = search(&set_of(|x| x.is_ascii_digit()));
+        assert_eq!(
+            digit[0].technique,
+            Technique::Range {
+                start: b'0',
+                len: 10
+            }
+        );
+
+        let alphabetic = search(&set_of(|x| x.is_ascii_alphabetic()));
+        assert_eq!(alphabetic[0].mask, 0b0010_0000);
+        assert_eq!(
+            alphabetic[0].technique,
+            Technique::Range {
+                start: b'a',
+                len: 26
+            }
+        );
+
+        let whitespace = search(&set_of(|x| x.is_ascii_whitespace()));
+        assert_eq!(
+            whitespace[0].technique,
+            Technique::Bitmap {
+                start: 0,
+                len: 33,
+                bits: 0b1_0000_0000_0000_0000_0011_0110_0000_0000,
+            }
+        );
+
+        let punctuation = search(&set_of(|x| x.is_ascii_punctuation()));
+        let strips = punctuation
+            .iter()
+            .find(|encoding| matches!(encoding.technique, Technique::Strips { .. }))
+            .unwrap();
+        assert_eq!(strips.offset, 6);
+        assert_eq!(
+            strips.technique,
+            Technique::Strips {
+                chunk_len: 32,
+                starts: vec![0, b'!' + 6, b':' + 6, b'[' + 6, b'{' + 6, 0, 0, 0],
+                lens: vec![0, 15, 7, 6, 4, 0, 0, 0],
+            }
+        );
+    }
+
+    #[test]
+    fn every_encoding_matches_its_set() {
+        check(&[false; 256]);
+        check(&[true; 256]);
+        for x in 0..=255 {
+            check(&set_of(|y| y == x));
+        }
+        // A cheap deterministic generator, so that the binary needs no dependencies.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..200 {
+            let mut set = [false; 256];
+            let strips = state % 6;
+            for _ in 0..strips {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let start = (state % 256) as usize;
+                let len = (state >> 8) as usize % 20;
+                set[start..(start + len).min(256)].fill(true);
+            }
+            check(&set);
+        }
+    }
+
+    #[test]
+    fn code_mentions_the_tables() {
+        let set = set_of(|x| x.is_ascii_punctuation());
+        let strips = search(&set)
+            .into_iter()
+            .find(|encoding| matches!(encoding.technique, Technique::Strips { .. }))
+            .unwrap();
+        let body = strips.body(&set, Input::U8, "*self");
+        assert!(body.contains("let x = self.wrapping_add(6);"), "{}", body);
+        assert!(
+            body.contains("[0, b'!' + 6, b':' + 6, b'[' + 6, b'{' + 6, 0, 0, 0]"),
+            "{}",
+            body
+        );
+        assert!(
+            body.contains("set: [(b'!', b'/'), (b':', b'@'), (b'[', b'`'), (b'{', b'~')]"),
+            "{}",
+            body
+        );
+        let body = strips.body(&set, Input::Char, "*self");
+        assert!(
+            body.contains("let x = (*self as u32).wrapping_add(6);"),
+            "{}",
+            body
+        );
+    }
+}
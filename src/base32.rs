@@ -0,0 +1,531 @@
+//! Base32 alphabets from RFC 4648, section 6, and from Douglas Crockford's
+//! specification, with a `no_std` codec that encodes into and decodes from
+//! caller-provided buffers. Both alphabets decode case-insensitively.
+
+use crate::class::AsciiClass;
+pub use crate::codec::{DecodeError, EncodeError, Padding};
+
+/// A base32 alphabet: 32 symbols for the 5-bit values, with `=` as padding.
+pub trait Base32Alphabet: AsciiClass {
+    /// The symbol for each 5-bit value. These are what the encoder writes.
+    const SYMBOLS: &'static [u8; 32];
+
+    /// Returns the 5-bit value of the symbol `x`, or `None` if `x` is not in the
+    /// alphabet.
+    fn value(&self, x: u8) -> Option<u8>;
+}
+
+/// The RFC 4648 base32 alphabet, `A-Z2-7`. Lowercase letters are accepted when
+/// decoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base32Standard;
+
+/// Crockford's base32 alphabet, `0-9A-Z` without `I`, `L`, `O`, and `U`.
+/// Lowercase letters are accepted when decoding, and the easily confused letters
+/// `I` and `L` decode as `1` and `O` decodes as `0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base32Crockford;
+
+impl AsciiClass for Base32Standard {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        // `| 0b0010_0000` folds uppercase letters onto lowercase ones. See
+        // `is_ascii_alphabetic_2`.
+        let letter = (x | 0b0010_0000).wrapping_sub(b'a');
+        let digit = x.wrapping_sub(b'2');
+        (letter < 26) | (digit < 6)
+    }
+}
+
+impl AsciiClass for Base32Crockford {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        // `| 0b0010_0000` folds uppercase letters onto lowercase ones. See
+        // `is_ascii_alphabetic_2`.
+        let letter = (x | 0b0010_0000).wrapping_sub(b'a');
+        let digit = x.wrapping_sub(b'0');
+        ((letter < 26) & (letter != b'u' - b'a')) | (digit < 10)
+    }
+}
+
+impl Base32Alphabet for Base32Standard {
+    const SYMBOLS: &'static [u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    #[inline]
+    fn value(&self, x: u8) -> Option<u8> {
+        let letter = (x | 0b0010_0000).wrapping_sub(b'a');
+        let digit = x.wrapping_sub(b'2');
+        if letter < 26 {
+            Some(letter)
+        } else if digit < 6 {
+            Some(digit + 26)
+        } else {
+            None
+        }
+    }
+}
+
+// Values of the case-folded letters `a` through `z`. `i` and `l` alias `1`, `o`
+// aliases `0`, and `u` is not in the alphabet.
+const CROCKFORD_LETTER_VALUES: [u8; 26] = [
+    10, 11, 12, 13, 14, 15, 16, 17, 1, 18, 19, 1, 20, 21, 0, 22, 23, 24, 25, 26, 0xFF, 27, 28, 29,
+    30, 31,
+];
+
+impl Base32Alphabet for Base32Crockford {
+    const SYMBOLS: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    #[inline]
+    fn value(&self, x: u8) -> Option<u8> {
+        let letter = (x | 0b0010_0000).wrapping_sub(b'a');
+        let digit = x.wrapping_sub(b'0');
+        if digit < 10 {
+            Some(digit)
+        } else if letter < 26 && letter != b'u' - b'a' {
+            Some(CROCKFORD_LETTER_VALUES[letter as usize])
+        } else {
+            None
+        }
+    }
+}
+
+// Number of symbols that encode a partial block of `i` bytes.
+const SYMBOLS_FOR_BYTES: [usize; 5] = [0, 2, 4, 5, 7];
+
+// Number of bytes decoded from a partial block of `i` symbols. `usize::MAX` marks
+// symbol counts that the encoder never produces.
+const BYTES_FOR_SYMBOLS: [usize; 8] = [0, usize::MAX, 1, usize::MAX, 2, 3, usize::MAX, 4];
+
+/// Returns the length of the encoding of `input_len` bytes.
+#[must_use]
+pub const fn encoded_len(input_len: usize, padding: Padding) -> usize {
+    match padding {
+        Padding::Strict => input_len.div_ceil(5) * 8,
+        Padding::Optional => input_len / 5 * 8 + SYMBOLS_FOR_BYTES[input_len % 5],
+    }
+}
+
+/// Encodes `input` into the start of `output` and returns the number of bytes
+/// written. With `Padding::Optional`, no padding is written. Crockford's base32 is
+/// normally written without padding.
+pub fn encode<A: Base32Alphabet>(
+    _alphabet: &A,
+    input: &[u8],
+    output: &mut [u8],
+    padding: Padding,
+) -> Result<usize, EncodeError> {
+    let required = encoded_len(input.len(), padding);
+    let output = match output.get_mut(..required) {
+        Some(output) => output,
+        None => return Err(EncodeError::OutputTooSmall { required }),
+    };
+
+    let encode_block = |block: &[u8], out: &mut [u8]| {
+        let mut bytes = [0; 8];
+        bytes[3..3 + block.len()].copy_from_slice(block);
+        let n = u64::from_be_bytes(bytes);
+        for (i, out) in out.iter_mut().enumerate() {
+            *out = A::SYMBOLS[(n >> (35 - 5 * i)) as usize & 31];
+        }
+    };
+
+    let mut chunks = input.chunks_exact(5);
+    for (chunk, out) in (&mut chunks).zip(output.chunks_exact_mut(8)) {
+        encode_block(chunk, out);
+    }
+
+    let remainder = chunks.remainder();
+    let out = &mut output[input.len() / 5 * 8..];
+    let (symbols, padding) = out.split_at_mut(SYMBOLS_FOR_BYTES[remainder.len()]);
+    encode_block(remainder, symbols);
+    padding.fill(b'=');
+
+    Ok(required)
+}
+
+/// Checks that `input` is a canonical encoding in `alphabet` without decoding it,
+/// and returns the length of the decoded data.
+pub fn validate<A: Base32Alphabet>(
+    alphabet: &A,
+    input: &[u8],
+    padding: Padding,
+) -> Result<usize, DecodeError> {
+    let (data, decoded_len) = split_padding(input, padding)?;
+    if let Some(index) = alphabet.find_nonmember(data) {
+        return Err(invalid_byte(data, index));
+    }
+    check_trailing_bits(alphabet, data, decoded_len)?;
+    Ok(decoded_len)
+}
+
+/// Decodes `input` into the start of `output` and returns the number of bytes
+/// written. If an error is returned, the contents of `output` are unspecified.
+pub fn decode<A: Base32Alphabet>(
+    alphabet: &A,
+    input: &[u8],
+    output: &mut [u8],
+    padding: Padding,
+) -> Result<usize, DecodeError> {
+    let (data, decoded_len) = split_padding(input, padding)?;
+    let output = match output.get_mut(..decoded_len) {
+        Some(output) => output,
+        None => {
+            return Err(DecodeError::OutputTooSmall {
+                required: decoded_len,
+            })
+        }
+    };
+
+    // Decodes up to eight symbols into the top of a 40-bit number.
+    let decode_block = |offset: usize, symbols: &[u8]| {
+        let mut n = 0;
+        for (i, &x) in symbols.iter().enumerate() {
+            match alphabet.value(x) {
+                Some(value) => n |= (value as u64) << (35 - 5 * i),
+                None => return Err(invalid_byte(data, offset + i)),
+            }
+        }
+        Ok(n.to_be_bytes())
+    };
+
+    let mut chunks = data.chunks_exact(8);
+    for (i, (chunk, out)) in (&mut chunks).zip(output.chunks_exact_mut(5)).enumerate() {
+        out.copy_from_slice(&decode_block(i * 8, chunk)?[3..]);
+    }
+
+    let remainder = chunks.remainder();
+    let out = &mut output[data.len() / 8 * 5..];
+    let bytes = decode_block(data.len() / 8 * 8, remainder)?;
+    out.copy_from_slice(&bytes[3..3 + out.len()]);
+    check_trailing_bits(alphabet, data, decoded_len)?;
+
+    Ok(decoded_len)
+}
+
+// Separates the symbols from the padding and checks the length and padding rules.
+// Returns the symbols and the decoded length.
+fn split_padding(input: &[u8], padding: Padding) -> Result<(&[u8], usize), DecodeError> {
+    let padding_len = input.iter().rev().take_while(|&&x| x == b'=').count();
+    let data = &input[..input.len() - padding_len];
+
+    if padding_len != 0 || padding == Padding::Strict {
+        if !input.len().is_multiple_of(8) {
+            return Err(if padding_len == 0 {
+                DecodeError::InvalidLength
+            } else {
+                DecodeError::InvalidPadding { index: data.len() }
+            });
+        }
+        // Padding always completes a partial block of 2, 4, 5 or 7 symbols, so it's 6,
+        // 4, 3 or 1 long, and the symbols of the last block must number exactly
+        // `8 - padding_len`.
+        if padding_len != 0
+            && (!matches!(padding_len, 1 | 3 | 4 | 6) || data.len() % 8 != 8 - padding_len)
+        {
+            return Err(DecodeError::InvalidPadding { index: data.len() });
+        }
+    }
+
+    match BYTES_FOR_SYMBOLS[data.len() % 8] {
+        usize::MAX if padding_len != 0 => Err(DecodeError::InvalidPadding { index: data.len() }),
+        usize::MAX => Err(DecodeError::InvalidLength),
+        bytes => Ok((data, data.len() / 8 * 5 + bytes)),
+    }
+}
+
+// The last symbol of a partial block carries bits past the end of the data. They
+// must be zero for the encoding to be canonical.
+fn check_trailing_bits<A: Base32Alphabet>(
+    alphabet: &A,
+    data: &[u8],
+    decoded_len: usize,
+) -> Result<(), DecodeError> {
+    let unused_bits = (5 * data.len() - 8 * decoded_len) as u32;
+    if unused_bits == 0 {
+        return Ok(());
+    }
+    let index = data.len() - 1;
+    match alphabet.value(data[index]) {
+        Some(value) if value & ((1 << unused_bits) - 1) == 0 => Ok(()),
+        Some(_) => Err(DecodeError::TrailingBits { index }),
+        None => Err(invalid_byte(data, index)),
+    }
+}
+
+fn invalid_byte(data: &[u8], index: usize) -> DecodeError {
+    match data[index] {
+        b'=' => DecodeError::InvalidPadding { index },
+        byte => DecodeError::InvalidByte { index, byte },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648, section 10.
+    const STANDARD_VECTORS: [(&[u8], &[u8]); 7] = [
+        (b"", b""),
+        (b"f", b"MY======"),
+        (b"fo", b"MZXQ===="),
+        (b"foo", b"MZXW6==="),
+        (b"foob", b"MZXW6YQ="),
+        (b"fooba", b"MZXW6YTB"),
+        (b"foobar", b"MZXW6YTBOI======"),
+    ];
+
+    // The same inputs in Crockford's alphabet, which is unpadded.
+    const CROCKFORD_VECTORS: [(&[u8], &[u8]); 7] = [
+        (b"", b""),
+        (b"f", b"CR"),
+        (b"fo", b"CSQG"),
+        (b"foo", b"CSQPY"),
+        (b"foob", b"CSQPYRG"),
+        (b"fooba", b"CSQPYRK1"),
+        (b"foobar", b"CSQPYRK1E8"),
+    ];
+
+    fn unpadded(encoded: &[u8]) -> &[u8] {
+        let len = encoded
+            .iter()
+            .position(|&x| x == b'=')
+            .unwrap_or(encoded.len());
+        &encoded[..len]
+    }
+
+    #[test]
+    fn standard_class_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                Base32Standard.contains(x)
+                    == Base32Standard::SYMBOLS.contains(&x.to_ascii_uppercase()),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn crockford_class_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                Base32Crockford.contains(x)
+                    == (Base32Crockford::SYMBOLS.contains(&x.to_ascii_uppercase())
+                        || b"IiLlOo".contains(&x)),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn values() {
+        for x in 0..=u8::MAX {
+            let upper = x.to_ascii_uppercase();
+            let standard = Base32Standard::SYMBOLS.iter().position(|&s| s == upper);
+            let crockford = match upper {
+                b'I' | b'L' => Some(1),
+                b'O' => Some(0),
+                _ => Base32Crockford::SYMBOLS.iter().position(|&s| s == upper),
+            };
+            assert_eq!(
+                Base32Standard.value(x).map(usize::from),
+                standard,
+                "Failed on {}",
+                x
+            );
+            assert_eq!(
+                Base32Crockford.value(x).map(usize::from),
+                crockford,
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn rfc_4648_encode() {
+        let mut buffer = [0; 16];
+        for (decoded, encoded) in STANDARD_VECTORS {
+            let len = encode(&Base32Standard, decoded, &mut buffer, Padding::Strict).unwrap();
+            assert_eq!(&buffer[..len], encoded);
+
+            let len = encode(&Base32Standard, decoded, &mut buffer, Padding::Optional).unwrap();
+            assert_eq!(&buffer[..len], unpadded(encoded));
+        }
+    }
+
+    #[test]
+    fn rfc_4648_decode() {
+        let mut buffer = [0; 6];
+        for (decoded, encoded) in STANDARD_VECTORS {
+            let len = decode(&Base32Standard, encoded, &mut buffer, Padding::Strict).unwrap();
+            assert_eq!(&buffer[..len], decoded);
+
+            let len = decode(
+                &Base32Standard,
+                unpadded(encoded),
+                &mut buffer,
+                Padding::Optional,
+            )
+            .unwrap();
+            assert_eq!(&buffer[..len], decoded);
+
+            let lowercase: [u8; 16] =
+                core::array::from_fn(|i| encoded.get(i).map_or(0, |x| x.to_ascii_lowercase()));
+            let lowercase = &lowercase[..encoded.len()];
+            let len = decode(&Base32Standard, lowercase, &mut buffer, Padding::Strict).unwrap();
+            assert_eq!(&buffer[..len], decoded);
+        }
+    }
+
+    #[test]
+    fn crockford_vectors() {
+        let mut encoded_buffer = [0; 10];
+        let mut decoded_buffer = [0; 6];
+        for (decoded, encoded) in CROCKFORD_VECTORS {
+            let len = encode(
+                &Base32Crockford,
+                decoded,
+                &mut encoded_buffer,
+                Padding::Optional,
+            )
+            .unwrap();
+            assert_eq!(&encoded_buffer[..len], encoded);
+
+            let len = decode(
+                &Base32Crockford,
+                encoded,
+                &mut decoded_buffer,
+                Padding::Optional,
+            )
+            .unwrap();
+            assert_eq!(&decoded_buffer[..len], decoded);
+        }
+    }
+
+    #[test]
+    fn crockford_aliases() {
+        let mut canonical = [0; 5];
+        let mut aliased = [0; 5];
+        decode(
+            &Base32Crockford,
+            b"01JK1M01",
+            &mut canonical,
+            Padding::Optional,
+        )
+        .unwrap();
+        decode(
+            &Base32Crockford,
+            b"oIjkLmOl",
+            &mut aliased,
+            Padding::Optional,
+        )
+        .unwrap();
+        assert_eq!(canonical, aliased);
+
+        assert_eq!(
+            decode(&Base32Crockford, b"CSQU", &mut aliased, Padding::Optional),
+            Err(DecodeError::InvalidByte {
+                index: 3,
+                byte: b'U'
+            })
+        );
+    }
+
+    fn round_trip_with<A: Base32Alphabet>(alphabet: &A) {
+        let input: [u8; 256] = core::array::from_fn(|i| (i * 167 + 13) as u8);
+        let mut encoded = [0; 416];
+        let mut decoded = [0; 256];
+        for len in 0..=input.len() {
+            for padding in [Padding::Strict, Padding::Optional] {
+                let n = encode(alphabet, &input[..len], &mut encoded, padding).unwrap();
+                assert_eq!(n, encoded_len(len, padding));
+                let m = decode(alphabet, &encoded[..n], &mut decoded, padding).unwrap();
+                assert_eq!(&decoded[..m], &input[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        round_trip_with(&Base32Standard);
+        round_trip_with(&Base32Crockford);
+    }
+
+    #[test]
+    fn padding_errors() {
+        let mut buffer = [0; 10];
+        let mut decode =
+            |input: &[u8], padding| decode(&Base32Standard, input, &mut buffer, padding);
+
+        assert_eq!(
+            decode(b"MY", Padding::Strict),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            decode(b"MY=====", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 2 })
+        );
+        assert_eq!(
+            decode(b"MZX=====", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 3 })
+        );
+        assert_eq!(
+            decode(b"MZXW6YT=", Padding::Optional),
+            Err(DecodeError::TrailingBits { index: 6 })
+        );
+        assert_eq!(
+            decode(b"M=======", Padding::Optional),
+            Err(DecodeError::InvalidPadding { index: 1 })
+        );
+        assert_eq!(
+            decode(b"MZX", Padding::Optional),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            decode(b"MY======MY======", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 2 })
+        );
+        assert_eq!(
+            decode(b"========", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 0 })
+        );
+        assert_eq!(
+            decode(b"MZXW6YTB========", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 8 })
+        );
+        assert_eq!(
+            decode(b"================", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 0 })
+        );
+        assert_eq!(
+            validate(&Base32Standard, b"========", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 0 })
+        );
+    }
+
+    #[test]
+    fn symbol_errors() {
+        let mut buffer = [0; 5];
+        assert_eq!(
+            decode(&Base32Standard, b"MZXW1YTB", &mut buffer, Padding::Strict),
+            Err(DecodeError::InvalidByte {
+                index: 4,
+                byte: b'1'
+            })
+        );
+        assert_eq!(
+            validate(&Base32Standard, b"MZXW1YTB", Padding::Strict),
+            Err(DecodeError::InvalidByte {
+                index: 4,
+                byte: b'1'
+            })
+        );
+        // "MZ" has nonzero bits after the single decoded byte.
+        assert_eq!(
+            validate(&Base32Standard, b"MZ======", Padding::Strict),
+            Err(DecodeError::TrailingBits { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! The Base58 alphabet used by Bitcoin and IPFS identifiers, with a `no_std` codec
+//! that encodes into and decodes from caller-provided buffers.
+//!
+//! Base58 treats the whole input as one big-endian number, so unlike base32 and
+//! base64 there are no blocks and no padding. Each leading zero byte is encoded as
+//! a leading `1`.
+
+use crate::class::{ascii_bitmap, in_ascii_bitmap, AsciiClass};
+pub use crate::codec::{DecodeError, EncodeError};
+
+/// The Base58 alphabet, `1-9A-Za-z` without `I`, `O`, and `l`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base58;
+
+/// The symbol for each base58 digit.
+pub const SYMBOLS: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const BITMAP: u128 = ascii_bitmap(&[
+    (b'1', b'9'),
+    (b'A', b'H'),
+    (b'J', b'N'),
+    (b'P', b'Z'),
+    (b'a', b'k'),
+    (b'm', b'z'),
+]);
+
+impl AsciiClass for Base58 {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        in_ascii_bitmap(BITMAP, x as u32)
+    }
+
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        in_ascii_bitmap(BITMAP, x as u32)
+    }
+}
+
+impl Base58 {
+    /// Returns the digit value of the symbol `x`, or `None` if `x` is not in the
+    /// alphabet.
+    #[must_use]
+    #[inline]
+    pub fn value(&self, x: u8) -> Option<u8> {
+        // Every strip of consecutive symbols starts at the value one past the end
+        // of the previous strip.
+        const STRIPS: [(u8, u8, u8); 6] = [
+            (b'1', 9, 0),
+            (b'A', 8, 9),
+            (b'J', 5, 17),
+            (b'P', 11, 22),
+            (b'a', 11, 33),
+            (b'm', 14, 44),
+        ];
+        for (start, length, first_value) in STRIPS {
+            let x = x.wrapping_sub(start);
+            if x < length {
+                return Some(x + first_value);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an upper bound on the length of the encoding of `input_len` bytes.
+/// Each byte needs at most log(256) / log(58) < 1.3658 digits.
+#[must_use]
+pub const fn encoded_len_max(input_len: usize) -> usize {
+    input_len + input_len.div_ceil(2)
+}
+
+/// Returns an upper bound on the length of the decoding of `input_len` symbols.
+#[must_use]
+pub const fn decoded_len_max(input_len: usize) -> usize {
+    input_len
+}
+
+/// Encodes `input` into the start of `output` and returns the number of bytes
+/// written.
+///
+/// The exact length is only known once the encoding is done, so if `output` runs
+/// out the error reports `encoded_len_max(input.len())` as the required length.
+pub fn encode(input: &[u8], output: &mut [u8]) -> Result<usize, EncodeError> {
+    let too_small = EncodeError::OutputTooSmall {
+        required: encoded_len_max(input.len()),
+    };
+
+    let zeros = input.iter().take_while(|&&x| x == 0).count();
+    if output.len() < zeros {
+        return Err(too_small);
+    }
+    let (leading, digits) = output.split_at_mut(zeros);
+    leading.fill(b'1');
+
+    // Little-endian base58 digits of the number, built with schoolbook
+    // multiplication by 256 for each input byte.
+    let mut len = 0;
+    for &x in &input[zeros..] {
+        let mut carry = x as u32;
+        for digit in &mut digits[..len] {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry != 0 {
+            match digits.get_mut(len) {
+                Some(digit) => *digit = (carry % 58) as u8,
+                None => return Err(too_small),
+            }
+            carry /= 58;
+            len += 1;
+        }
+    }
+
+    let digits = &mut digits[..len];
+    digits.reverse();
+    for digit in digits {
+        *digit = SYMBOLS[*digit as usize];
+    }
+    Ok(zeros + len)
+}
+
+/// Decodes `input` into the start of `output` and returns the number of bytes
+/// written. If an error is returned, the contents of `output` are unspecified.
+///
+/// The exact length is only known once the decoding is done, so if `output` runs
+/// out the error reports `decoded_len_max(input.len())` as the required length.
+pub fn decode(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    let too_small = DecodeError::OutputTooSmall {
+        required: decoded_len_max(input.len()),
+    };
+    if let Some(index) = Base58.find_nonmember(input) {
+        return Err(DecodeError::InvalidByte {
+            index,
+            byte: input[index],
+        });
+    }
+
+    let zeros = input.iter().take_while(|&&x| x == b'1').count();
+    if output.len() < zeros {
+        return Err(too_small);
+    }
+    let (leading, bytes) = output.split_at_mut(zeros);
+    leading.fill(0);
+
+    // Little-endian bytes of the number, built with schoolbook multiplication by
+    // 58 for each symbol.
+    let mut len = 0;
+    for &x in &input[zeros..] {
+        let mut carry = Base58.value(x).unwrap_or(0) as u32;
+        for byte in &mut bytes[..len] {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry != 0 {
+            match bytes.get_mut(len) {
+                Some(byte) => *byte = carry as u8,
+                None => return Err(too_small),
+            }
+            carry >>= 8;
+            len += 1;
+        }
+    }
+
+    bytes[..len].reverse();
+    Ok(zeros + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // draft-msporny-base58, section 5.
+    const VECTORS: [(&[u8], &[u8]); 6] = [
+        (b"", b""),
+        (b"Hello World!", b"2NEpo7TZRRrLZSi2U"),
+        (
+            b"The quick brown fox jumps over the lazy dog.",
+            b"USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z",
+        ),
+        (b"\x00\x00\x28\x7f\xb4\xcd", b"11233QC4"),
+        (b"\x00", b"1"),
+        (b"\x00\x00\xff", b"115Q"),
+    ];
+
+    #[test]
+    fn class_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                Base58.contains(x) == SYMBOLS.contains(&x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn class_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                Base58.contains_char(x) == (x.is_ascii() && SYMBOLS.contains(&(x as u8))),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn values() {
+        for x in 0..=u8::MAX {
+            let expected = SYMBOLS.iter().position(|&s| s == x);
+            assert_eq!(
+                Base58.value(x).map(usize::from),
+                expected,
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn draft_vectors() {
+        let mut encoded = [0; 64];
+        let mut decoded = [0; 64];
+        for (bytes, symbols) in VECTORS {
+            let len = encode(bytes, &mut encoded).unwrap();
+            assert_eq!(&encoded[..len], symbols);
+            let len = decode(symbols, &mut decoded).unwrap();
+            assert_eq!(&decoded[..len], bytes);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let input: [u8; 64] = core::array::from_fn(|i| (i * 167 + 13) as u8);
+        let mut encoded = [0; encoded_len_max(64)];
+        let mut decoded = [0; 64];
+        for start in 0..input.len() {
+            for zeros in 0..3 {
+                let mut bytes = input;
+                bytes[start..(start + zeros).min(64)].fill(0);
+                let bytes = &bytes[start..];
+                let n = encode(bytes, &mut encoded).unwrap();
+                assert!(n <= encoded_len_max(bytes.len()));
+                let m = decode(&encoded[..n], &mut decoded).unwrap();
+                assert_eq!(&decoded[..m], bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut buffer = [0; 16];
+        assert_eq!(
+            decode(b"2NEpo7T0RR", &mut buffer),
+            Err(DecodeError::InvalidByte {
+                index: 7,
+                byte: b'0'
+            })
+        );
+        assert_eq!(
+            decode(b"2NEpo7TZRRrLZSi2U", &mut buffer[..11]),
+            Err(DecodeError::OutputTooSmall { required: 17 })
+        );
+        assert_eq!(
+            encode(b"Hello World!", &mut buffer),
+            Err(EncodeError::OutputTooSmall { required: 18 })
+        );
+    }
+}
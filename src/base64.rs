@@ -0,0 +1,566 @@
+//! Base64 alphabets from RFC 4648, sections 4 and 5, and a `no_std` codec that
+//! encodes into and decodes from caller-provided buffers.
+
+use crate::class::{ascii_bitmap, in_ascii_bitmap, AsciiClass};
+pub use crate::codec::{DecodeError, EncodeError, Padding};
+
+/// A base64 alphabet: 64 symbols for the 6-bit values, with `=` as padding.
+pub trait Base64Alphabet: AsciiClass {
+    /// The symbol for each 6-bit value.
+    const SYMBOLS: &'static [u8; 64];
+
+    /// Returns the 6-bit value of the symbol `x`, or `None` if `x` is not in the
+    /// alphabet.
+    fn value(&self, x: u8) -> Option<u8>;
+}
+
+/// The standard base64 alphabet, `A-Za-z0-9+/`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base64Standard;
+
+/// The URL and filename safe base64 alphabet, `A-Za-z0-9-_`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base64UrlSafe;
+
+// Standard base64 in 16-codepoint chunks, after adding 1 so that `+` and `/` are in
+// separate chunks. A `char` is tested as a `u32`, so it can't wrap into a strip.
+#[inline]
+fn is_standard(x: u32) -> bool {
+    let x = x.wrapping_add(1);
+    handle_strip_of_each_chunk!(
+        x,
+        u32,
+        16,
+        [
+            0,
+            0,
+            b'+' + 1,
+            b'/' + 1,
+            b'A' + 1,
+            b'O' + 1,
+            b'a' + 1,
+            b'o' + 1,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0
+        ],
+        [0, 0, 1, 11, 14, 12, 14, 12, 0, 0, 0, 0, 0, 0, 0, 0],
+        offset: 1,
+        set: [(b'+', b'+'), (b'/', b'9'), (b'A', b'Z'), (b'a', b'z')]
+    )
+}
+
+const URL_SAFE_BITMAP: u128 = ascii_bitmap(&[
+    (b'-', b'-'),
+    (b'0', b'9'),
+    (b'A', b'Z'),
+    (b'_', b'_'),
+    (b'a', b'z'),
+]);
+
+impl AsciiClass for Base64Standard {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        is_standard(x as u32)
+    }
+
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        is_standard(x as u32)
+    }
+}
+
+impl AsciiClass for Base64UrlSafe {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        in_ascii_bitmap(URL_SAFE_BITMAP, x as u32)
+    }
+
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        in_ascii_bitmap(URL_SAFE_BITMAP, x as u32)
+    }
+}
+
+impl Base64Alphabet for Base64Standard {
+    const SYMBOLS: &'static [u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    #[inline]
+    fn value(&self, x: u8) -> Option<u8> {
+        value(x, b'+', b'/')
+    }
+}
+
+impl Base64Alphabet for Base64UrlSafe {
+    const SYMBOLS: &'static [u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    #[inline]
+    fn value(&self, x: u8) -> Option<u8> {
+        value(x, b'-', b'_')
+    }
+}
+
+// The alphabets only differ in the symbols for 62 and 63. The letters and digits are
+// each one strip of consecutive codepoints, so their values are found the same way
+// as in `is_ascii_uppercase_2` and friends.
+#[inline]
+fn value(x: u8, symbol_62: u8, symbol_63: u8) -> Option<u8> {
+    let upper = x.wrapping_sub(b'A');
+    let lower = x.wrapping_sub(b'a');
+    let digit = x.wrapping_sub(b'0');
+    if upper < 26 {
+        Some(upper)
+    } else if lower < 26 {
+        Some(lower + 26)
+    } else if digit < 10 {
+        Some(digit + 52)
+    } else if x == symbol_62 {
+        Some(62)
+    } else if x == symbol_63 {
+        Some(63)
+    } else {
+        None
+    }
+}
+
+/// Returns the length of the encoding of `input_len` bytes.
+#[must_use]
+pub const fn encoded_len(input_len: usize, padding: Padding) -> usize {
+    match padding {
+        Padding::Strict => input_len.div_ceil(3) * 4,
+        Padding::Optional => input_len / 3 * 4 + [0, 2, 3][input_len % 3],
+    }
+}
+
+/// Encodes `input` into the start of `output` and returns the number of bytes
+/// written. With `Padding::Optional`, no padding is written.
+pub fn encode<A: Base64Alphabet>(
+    _alphabet: &A,
+    input: &[u8],
+    output: &mut [u8],
+    padding: Padding,
+) -> Result<usize, EncodeError> {
+    let required = encoded_len(input.len(), padding);
+    let output = match output.get_mut(..required) {
+        Some(output) => output,
+        None => return Err(EncodeError::OutputTooSmall { required }),
+    };
+
+    let symbols = A::SYMBOLS;
+    let mut chunks = input.chunks_exact(3);
+    let mut out = output.chunks_exact_mut(4);
+    for (chunk, out) in (&mut chunks).zip(&mut out) {
+        let n = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+        out[0] = symbols[(n >> 18) as usize & 63];
+        out[1] = symbols[(n >> 12) as usize & 63];
+        out[2] = symbols[(n >> 6) as usize & 63];
+        out[3] = symbols[n as usize & 63];
+    }
+
+    let out = &mut output[input.len() / 3 * 4..];
+    match *chunks.remainder() {
+        [a] => {
+            out[0] = symbols[(a >> 2) as usize];
+            out[1] = symbols[((a & 0b11) << 4) as usize];
+            if padding == Padding::Strict {
+                out[2..4].copy_from_slice(b"==");
+            }
+        }
+        [a, b] => {
+            out[0] = symbols[(a >> 2) as usize];
+            out[1] = symbols[(((a & 0b11) << 4) | (b >> 4)) as usize];
+            out[2] = symbols[((b & 0b1111) << 2) as usize];
+            if padding == Padding::Strict {
+                out[3] = b'=';
+            }
+        }
+        _ => {}
+    }
+
+    Ok(required)
+}
+
+/// Checks that `input` is a canonical encoding in `alphabet` without decoding it,
+/// and returns the length of the decoded data.
+pub fn validate<A: Base64Alphabet>(
+    alphabet: &A,
+    input: &[u8],
+    padding: Padding,
+) -> Result<usize, DecodeError> {
+    let (data, decoded_len) = split_padding(input, padding)?;
+    if let Some(index) = alphabet.find_nonmember(data) {
+        return Err(invalid_byte(data, index));
+    }
+    check_trailing_bits(alphabet, data)?;
+    Ok(decoded_len)
+}
+
+/// Decodes `input` into the start of `output` and returns the number of bytes
+/// written. If an error is returned, the contents of `output` are unspecified.
+pub fn decode<A: Base64Alphabet>(
+    alphabet: &A,
+    input: &[u8],
+    output: &mut [u8],
+    padding: Padding,
+) -> Result<usize, DecodeError> {
+    let (data, decoded_len) = split_padding(input, padding)?;
+    let output = match output.get_mut(..decoded_len) {
+        Some(output) => output,
+        None => {
+            return Err(DecodeError::OutputTooSmall {
+                required: decoded_len,
+            })
+        }
+    };
+
+    let values = |offset: usize, symbols: &[u8]| {
+        let mut n = 0;
+        for (i, &x) in symbols.iter().enumerate() {
+            match alphabet.value(x) {
+                Some(value) => n = (n << 6) | value as u32,
+                None => return Err(invalid_byte(data, offset + i)),
+            }
+        }
+        Ok(n)
+    };
+
+    let mut chunks = data.chunks_exact(4);
+    for (i, (chunk, out)) in (&mut chunks).zip(output.chunks_exact_mut(3)).enumerate() {
+        let [_, a, b, c] = values(i * 4, chunk)?.to_be_bytes();
+        out.copy_from_slice(&[a, b, c]);
+    }
+
+    let offset = data.len() / 4 * 4;
+    let out = &mut output[offset / 4 * 3..];
+    match *chunks.remainder() {
+        [_, _] => {
+            let n = values(offset, chunks.remainder())?;
+            out[0] = (n >> 4) as u8;
+        }
+        [_, _, _] => {
+            let n = values(offset, chunks.remainder())?;
+            out.copy_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]);
+        }
+        _ => {}
+    }
+    check_trailing_bits(alphabet, data)?;
+
+    Ok(decoded_len)
+}
+
+// Separates the symbols from the padding and checks the length and padding rules.
+// Returns the symbols and the decoded length.
+fn split_padding(input: &[u8], padding: Padding) -> Result<(&[u8], usize), DecodeError> {
+    let padding_len = match input {
+        [.., b'=', b'='] => 2,
+        [.., b'='] => 1,
+        _ => 0,
+    };
+    let data = &input[..input.len() - padding_len];
+
+    if padding_len != 0 || padding == Padding::Strict {
+        if !input.len().is_multiple_of(4) {
+            return Err(if padding_len == 0 {
+                DecodeError::InvalidLength
+            } else {
+                DecodeError::InvalidPadding { index: data.len() }
+            });
+        }
+        // Padding always completes a partial block, so the symbols of the last
+        // block must number exactly `4 - padding_len`.
+        if padding_len != 0 && data.len() % 4 != 4 - padding_len {
+            return Err(DecodeError::InvalidPadding { index: data.len() });
+        }
+    }
+
+    let decoded_len = data.len() / 4 * 3
+        + match data.len() % 4 {
+            0 => 0,
+            1 => return Err(DecodeError::InvalidLength),
+            2 => 1,
+            _ => 2,
+        };
+    Ok((data, decoded_len))
+}
+
+// The last symbol of a partial block carries bits past the end of the data. They
+// must be zero for the encoding to be canonical.
+fn check_trailing_bits<A: Base64Alphabet>(alphabet: &A, data: &[u8]) -> Result<(), DecodeError> {
+    let unused_bits_mask = match data.len() % 4 {
+        2 => 0b1111,
+        3 => 0b11,
+        _ => return Ok(()),
+    };
+    let index = data.len() - 1;
+    match alphabet.value(data[index]) {
+        Some(value) if value & unused_bits_mask == 0 => Ok(()),
+        Some(_) => Err(DecodeError::TrailingBits { index }),
+        None => Err(invalid_byte(data, index)),
+    }
+}
+
+fn invalid_byte(data: &[u8], index: usize) -> DecodeError {
+    match data[index] {
+        b'=' => DecodeError::InvalidPadding { index },
+        byte => DecodeError::InvalidByte { index, byte },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648, section 10.
+    const VECTORS: [(&[u8], &[u8]); 7] = [
+        (b"", b""),
+        (b"f", b"Zg=="),
+        (b"fo", b"Zm8="),
+        (b"foo", b"Zm9v"),
+        (b"foob", b"Zm9vYg=="),
+        (b"fooba", b"Zm9vYmE="),
+        (b"foobar", b"Zm9vYmFy"),
+    ];
+
+    fn unpadded(encoded: &[u8]) -> &[u8] {
+        let len = encoded
+            .iter()
+            .position(|&x| x == b'=')
+            .unwrap_or(encoded.len());
+        &encoded[..len]
+    }
+
+    #[test]
+    fn standard_class_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                Base64Standard.contains(x) == Base64Standard::SYMBOLS.contains(&x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn url_safe_class_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                Base64UrlSafe.contains(x) == Base64UrlSafe::SYMBOLS.contains(&x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn standard_class_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                Base64Standard.contains_char(x)
+                    == (x.is_ascii() && Base64Standard::SYMBOLS.contains(&(x as u8))),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn url_safe_class_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                Base64UrlSafe.contains_char(x)
+                    == (x.is_ascii() && Base64UrlSafe::SYMBOLS.contains(&(x as u8))),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn values() {
+        for x in 0..=u8::MAX {
+            let standard = Base64Standard::SYMBOLS.iter().position(|&s| s == x);
+            let url_safe = Base64UrlSafe::SYMBOLS.iter().position(|&s| s == x);
+            assert_eq!(
+                Base64Standard.value(x).map(usize::from),
+                standard,
+                "Failed on {}",
+                x
+            );
+            assert_eq!(
+                Base64UrlSafe.value(x).map(usize::from),
+                url_safe,
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn rfc_4648_encode() {
+        let mut buffer = [0; 8];
+        for (decoded, encoded) in VECTORS {
+            let len = encode(&Base64Standard, decoded, &mut buffer, Padding::Strict).unwrap();
+            assert_eq!(&buffer[..len], encoded);
+
+            let len = encode(&Base64Standard, decoded, &mut buffer, Padding::Optional).unwrap();
+            assert_eq!(&buffer[..len], unpadded(encoded));
+        }
+    }
+
+    #[test]
+    fn rfc_4648_decode() {
+        let mut buffer = [0; 6];
+        for (decoded, encoded) in VECTORS {
+            let len = decode(&Base64Standard, encoded, &mut buffer, Padding::Strict).unwrap();
+            assert_eq!(&buffer[..len], decoded);
+
+            let len = decode(&Base64Standard, encoded, &mut buffer, Padding::Optional).unwrap();
+            assert_eq!(&buffer[..len], decoded);
+
+            let len = decode(
+                &Base64Standard,
+                unpadded(encoded),
+                &mut buffer,
+                Padding::Optional,
+            )
+            .unwrap();
+            assert_eq!(&buffer[..len], decoded);
+
+            assert_eq!(
+                validate(&Base64Standard, encoded, Padding::Strict),
+                Ok(decoded.len())
+            );
+        }
+    }
+
+    #[test]
+    fn url_safe_differs_from_standard() {
+        let mut buffer = [0; 4];
+        let len = encode(&Base64Standard, &[0xFB, 0xFF], &mut buffer, Padding::Strict).unwrap();
+        assert_eq!(&buffer[..len], b"+/8=");
+        let len = encode(&Base64UrlSafe, &[0xFB, 0xFF], &mut buffer, Padding::Strict).unwrap();
+        assert_eq!(&buffer[..len], b"-_8=");
+
+        assert_eq!(
+            validate(&Base64Standard, b"-_8=", Padding::Strict),
+            Err(DecodeError::InvalidByte {
+                index: 0,
+                byte: b'-'
+            })
+        );
+        assert_eq!(validate(&Base64UrlSafe, b"-_8=", Padding::Strict), Ok(2));
+    }
+
+    fn round_trip_with<A: Base64Alphabet>(alphabet: &A) {
+        let input: [u8; 256] = core::array::from_fn(|i| (i * 167 + 13) as u8);
+        let mut encoded = [0; 344];
+        let mut decoded = [0; 256];
+        for len in 0..=input.len() {
+            for padding in [Padding::Strict, Padding::Optional] {
+                let n = encode(alphabet, &input[..len], &mut encoded, padding).unwrap();
+                assert_eq!(n, encoded_len(len, padding));
+                let m = decode(alphabet, &encoded[..n], &mut decoded, padding).unwrap();
+                assert_eq!(&decoded[..m], &input[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        round_trip_with(&Base64Standard);
+        round_trip_with(&Base64UrlSafe);
+    }
+
+    #[test]
+    fn padding_errors() {
+        let mut buffer = [0; 6];
+        let mut decode =
+            |input: &[u8], padding| decode(&Base64Standard, input, &mut buffer, padding);
+
+        assert_eq!(
+            decode(b"Zg", Padding::Strict),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            decode(b"Zg=", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 2 })
+        );
+        assert_eq!(
+            decode(b"Zg=", Padding::Optional),
+            Err(DecodeError::InvalidPadding { index: 2 })
+        );
+        assert_eq!(
+            decode(b"Zm8==", Padding::Optional),
+            Err(DecodeError::InvalidPadding { index: 3 })
+        );
+        assert_eq!(
+            decode(b"Zm9v=", Padding::Optional),
+            Err(DecodeError::InvalidPadding { index: 4 })
+        );
+        assert_eq!(
+            decode(b"Zm==Zm9v", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 2 })
+        );
+        assert_eq!(
+            decode(b"Z===", Padding::Strict),
+            Err(DecodeError::InvalidPadding { index: 1 })
+        );
+        assert_eq!(
+            decode(b"Z", Padding::Optional),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            decode(b"Zm9vY", Padding::Optional),
+            Err(DecodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn symbol_errors() {
+        let mut buffer = [0; 6];
+        assert_eq!(
+            decode(&Base64Standard, b"Zm9v Zm9", &mut buffer, Padding::Optional),
+            Err(DecodeError::InvalidByte {
+                index: 4,
+                byte: b' '
+            })
+        );
+        assert_eq!(
+            decode(&Base64Standard, b"Zm9vZ\0==", &mut buffer, Padding::Strict),
+            Err(DecodeError::InvalidByte { index: 5, byte: 0 })
+        );
+        assert_eq!(
+            validate(&Base64Standard, b"Zm9vZ\0==", Padding::Strict),
+            Err(DecodeError::InvalidByte { index: 5, byte: 0 })
+        );
+        // "Zh==" has nonzero bits after the single decoded byte.
+        assert_eq!(
+            decode(&Base64Standard, b"Zh==", &mut buffer, Padding::Strict),
+            Err(DecodeError::TrailingBits { index: 1 })
+        );
+        assert_eq!(
+            validate(&Base64Standard, b"Zm9=", Padding::Strict),
+            Err(DecodeError::TrailingBits { index: 2 })
+        );
+    }
+
+    #[test]
+    fn output_too_small() {
+        let mut buffer = [0; 5];
+        assert_eq!(
+            encode(&Base64Standard, b"fooba", &mut buffer, Padding::Strict),
+            Err(EncodeError::OutputTooSmall { required: 8 })
+        );
+        assert_eq!(
+            decode(&Base64Standard, b"Zm9vYmFy", &mut buffer, Padding::Strict),
+            Err(DecodeError::OutputTooSmall { required: 6 })
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! POSIX bracket expressions like `[[:alnum:]_.-]`, parsed at runtime into a set of
+//! bytes, for character sets that come from configuration rather than code.
+//!
+//! The syntax is that of `ascii_class!` and the `"C"` locale: a `]` right after the
+//! opening `[` or `[^` is a byte, and so is a `-` at either end, there are no
+//! escapes, and the named classes are those of the `posix` module. Collating
+//! symbols and equivalence classes like `[.-.]` and `[=a=]` can only name a single
+//! character, as every character is its own collating element in the `"C"` locale.
+
+use core::fmt::{self, Write};
+use core::ops::{BitAnd, BitOr, Not};
+use core::str::FromStr;
+
+use crate::class::{in_ascii_bitmap, AsciiClass};
+use crate::posix::IsPosix2;
+
+/// A set of bytes that a bracket expression can describe: any set of ASCII bytes,
+/// along with either all or none of the bytes from 128 up. A negated expression
+/// contains all of them, and any other contains none.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+    ascii: u128,
+    high: bool,
+}
+
+impl ByteSet {
+    pub const EMPTY: Self = ByteSet {
+        ascii: 0,
+        high: false,
+    };
+    pub const ALL: Self = ByteSet {
+        ascii: u128::MAX,
+        high: true,
+    };
+
+    /// Parses a bracket expression. The whole of `expression` must be one bracket
+    /// expression, and it must be ASCII.
+    pub fn parse(expression: &str) -> Result<Self, ParseError> {
+        let bytes = expression.as_bytes();
+        if let Some(index) = bytes.iter().position(|x| !x.is_ascii()) {
+            return Err(ParseError::NonAscii { index });
+        }
+        if bytes.first() != Some(&b'[') {
+            return Err(ParseError::MissingOpenBracket);
+        }
+        let negated = bytes.get(1) == Some(&b'^');
+        let first = 1 + negated as usize;
+
+        let mut ascii = 0;
+        let mut i = first;
+        loop {
+            match &bytes[i..] {
+                [] => return Err(ParseError::MissingCloseBracket),
+                [b']', ..] if i != first => break,
+                [b'[', b':', ..] => {
+                    let (is_member, after) = class(expression, i)?;
+                    ascii |= (0..128).filter(is_member).fold(0, |bits, x| bits | 1 << x);
+                    i = after;
+                }
+                [b'[', b'=', ..] => {
+                    let (x, after) = element(bytes, i)?;
+                    ascii |= 1 << x;
+                    i = after;
+                }
+                _ => {
+                    let (start, after) = element(bytes, i)?;
+                    // A `-` right before the closing `]` is a byte, not a range.
+                    match &bytes[after..] {
+                        [b'-', end, ..] if *end != b']' => {
+                            let (end, after) = element(bytes, after + 1)?;
+                            if start > end {
+                                return Err(ParseError::BackwardsRange { index: i });
+                            }
+                            ascii |= (u128::MAX >> (127 - end)) & (u128::MAX << start);
+                            i = after;
+                        }
+                        _ => {
+                            ascii |= 1 << start;
+                            i = after;
+                        }
+                    }
+                }
+            }
+        }
+        if i + 1 != bytes.len() {
+            return Err(ParseError::TrailingInput { index: i + 1 });
+        }
+
+        let set = ByteSet { ascii, high: false };
+        Ok(if negated { !set } else { set })
+    }
+
+    /// Returns the set of ASCII bytes whose bits are set in `ascii`, where bit `x`
+    /// stands for the byte `x`.
+    #[must_use]
+    #[inline]
+    pub const fn from_ascii_bits(ascii: u128) -> Self {
+        ByteSet { ascii, high: false }
+    }
+
+    /// Returns a bitmap of the ASCII bytes in the set, where bit `x` is 1 iff `x` is
+    /// in the set.
+    #[must_use]
+    #[inline]
+    pub const fn ascii_bits(self) -> u128 {
+        self.ascii
+    }
+
+    /// Returns `true` iff the bytes from 128 up are in the set.
+    #[must_use]
+    #[inline]
+    pub const fn contains_high(self) -> bool {
+        self.high
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.ascii == 0 && !self.high
+    }
+}
+
+/// An error from parsing a bracket expression. Indexes are byte offsets into the
+/// expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// The expression doesn't start with `[`.
+    MissingOpenBracket,
+    /// The expression has no closing `]`.
+    MissingCloseBracket,
+    /// There's more after the closing `]`, starting at `index`.
+    TrailingInput { index: usize },
+    /// The byte at `index` isn't ASCII.
+    NonAscii { index: usize },
+    /// The range that starts at `index` ends before it starts, like `z-a`.
+    BackwardsRange { index: usize },
+    /// The `[:` at `index` isn't followed by the name of a POSIX class and `:]`.
+    UnknownClass { index: usize },
+    /// The `[.` or `[=` at `index` isn't followed by one character and a closing
+    /// `.]` or `=]`.
+    InvalidCollatingElement { index: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseError::MissingOpenBracket => f.write_str("bracket expression must start with `[`"),
+            ParseError::MissingCloseBracket => f.write_str("bracket expression has no closing `]`"),
+            ParseError::TrailingInput { index } => {
+                write!(
+                    f,
+                    "unexpected input after the closing `]` at index {}",
+                    index
+                )
+            }
+            ParseError::NonAscii { index } => write!(f, "non-ASCII byte at index {}", index),
+            ParseError::BackwardsRange { index } => {
+                write!(f, "backwards range at index {}", index)
+            }
+            ParseError::UnknownClass { index } => {
+                write!(f, "unknown character class at index {}", index)
+            }
+            ParseError::InvalidCollatingElement { index } => {
+                write!(f, "invalid collating element at index {}", index)
+            }
+        }
+    }
+}
+
+// The classes of the `"C"` locale, in terms of the `IsAscii2`-based `IsPosix2`.
+type Predicate = fn(&u8) -> bool;
+
+const CLASSES: [(&str, Predicate); 12] = [
+    ("alnum", u8::is_posix_alnum_2),
+    ("alpha", u8::is_posix_alpha_2),
+    ("blank", u8::is_posix_blank_2),
+    ("cntrl", u8::is_posix_cntrl_2),
+    ("digit", u8::is_posix_digit_2),
+    ("graph", u8::is_posix_graph_2),
+    ("lower", u8::is_posix_lower_2),
+    ("print", u8::is_posix_print_2),
+    ("punct", u8::is_posix_punct_2),
+    ("space", u8::is_posix_space_2),
+    ("upper", u8::is_posix_upper_2),
+    ("xdigit", u8::is_posix_xdigit_2),
+];
+
+// Parses the `[:name:]` at `index`, returning the class and the index after it.
+fn class(expression: &str, index: usize) -> Result<(Predicate, usize), ParseError> {
+    let error = ParseError::UnknownClass { index };
+    let name_start = index + 2;
+    let name_len = expression[name_start..].find(":]").ok_or(error)?;
+    let name = &expression[name_start..name_start + name_len];
+    let (_, is_member) = CLASSES
+        .iter()
+        .find(|(class, _)| *class == name)
+        .ok_or(error)?;
+    Ok((*is_member, name_start + name_len + 2))
+}
+
+// Parses the byte, `[.x.]` or `[=x=]` at `index`, returning the byte and the index
+// after it.
+fn element(bytes: &[u8], index: usize) -> Result<(u8, usize), ParseError> {
+    match bytes[index..] {
+        [b'[', delimiter @ (b'.' | b'='), ..] => match bytes[index + 2..] {
+            [x, end, b']', ..] if end == delimiter => Ok((x, index + 5)),
+            _ => Err(ParseError::InvalidCollatingElement { index }),
+        },
+        _ => Ok((bytes[index], index + 1)),
+    }
+}
+
+impl FromStr for ByteSet {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(expression: &str) -> Result<Self, ParseError> {
+        ByteSet::parse(expression)
+    }
+}
+
+/// Writes the set as a bracket expression that parses back to the same set, with
+/// runs of three or more bytes as ranges. A set that contains the bytes from 128 up
+/// is written negated.
+///
+/// The empty set and the set of every byte can't be written as bracket
+/// expressions. They're written as `[]` and `[^]`, which don't parse.
+impl fmt::Display for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (open, members) = if self.high {
+            ("[^", !self.ascii)
+        } else {
+            ("[", self.ascii)
+        };
+        let has = |x: u8| x < 128 && (members >> x) & 1 != 0;
+        // Runs are split at these, so that no range starts or ends with one.
+        let special = |x: u8| matches!(x, b'-' | b'[' | b']' | b'^');
+
+        f.write_str(open)?;
+        // `]` is only a byte first, and `-` only last.
+        let mut written = has(b']');
+        if written {
+            f.write_char(']')?;
+        }
+        let mut x = 0;
+        while x < 128 {
+            if !has(x) || x == b']' || x == b'-' {
+                x += 1;
+                continue;
+            }
+            if special(x) {
+                if x == b'^' && !written && !self.high {
+                    // A `^` right after the opening `[` would negate the set.
+                    f.write_str("[.^.]")?;
+                } else {
+                    f.write_char(x as char)?;
+                }
+                written = true;
+                x += 1;
+                continue;
+            }
+            let mut end = x;
+            while has(end + 1) && !special(end + 1) {
+                end += 1;
+            }
+            match end - x {
+                0 => f.write_char(x as char)?,
+                1 => {
+                    f.write_char(x as char)?;
+                    f.write_char(end as char)?;
+                }
+                _ => write!(f, "{}-{}", x as char, end as char)?,
+            }
+            written = true;
+            x = end + 1;
+        }
+        if has(b'-') {
+            f.write_char('-')?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl AsciiClass for ByteSet {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        in_ascii_bitmap(self.ascii, x as u32) | (x >= 128 && self.high)
+    }
+}
+
+impl BitOr for ByteSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        ByteSet {
+            ascii: self.ascii | other.ascii,
+            high: self.high | other.high,
+        }
+    }
+}
+
+impl BitAnd for ByteSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        ByteSet {
+            ascii: self.ascii & other.ascii,
+            high: self.high & other.high,
+        }
+    }
+}
+
+impl Not for ByteSet {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        ByteSet {
+            ascii: !self.ascii,
+            high: !self.high,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn members(expression: &str) -> Vec<u8> {
+        let set = ByteSet::parse(expression).unwrap();
+        (0..=u8::MAX).filter(|&x| set.contains(x)).collect()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(members("[0-2a_-]"), b"-012_a");
+        assert_eq!(members("[]a-]"), b"-]a");
+        assert_eq!(members("[^]]").len(), 255);
+        assert_eq!(members("[[:digit:]x]"), b"0123456789x");
+        assert_eq!(members("[[:blank:]]"), b"\t ");
+        assert_eq!(members("[[:space:]]"), b"\t\n\x0B\x0C\r ");
+        assert_eq!(members("[^[:print:][:cntrl:]]").len(), 128);
+        assert_eq!(members(r"[\n]"), b"\\n");
+        assert_eq!(members("[[.-.]-/]"), b"-./");
+        assert_eq!(members("[[=^=]]"), b"^");
+        assert_eq!(members("[^^]").len(), 255);
+        assert_eq!(members("[a^]"), b"^a");
+        assert_eq!(members("[[]"), b"[");
+        assert_eq!(members("[--/]"), b"-./");
+    }
+
+    #[test]
+    fn classes_match_posix() {
+        for (name, is_member) in CLASSES {
+            let set = ByteSet::parse(&format!("[[:{}:]]", name)).unwrap();
+            for x in 0..=u8::MAX {
+                assert!(
+                    set.contains(x) == is_member(&x),
+                    "Failed on {} in {}",
+                    x,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let error = |expression| ByteSet::parse(expression).unwrap_err();
+        assert_eq!(error("abc"), ParseError::MissingOpenBracket);
+        assert_eq!(error(""), ParseError::MissingOpenBracket);
+        assert_eq!(error("[abc"), ParseError::MissingCloseBracket);
+        assert_eq!(error("[]"), ParseError::MissingCloseBracket);
+        assert_eq!(error("[^]"), ParseError::MissingCloseBracket);
+        assert_eq!(error("[a]b"), ParseError::TrailingInput { index: 3 });
+        assert_eq!(error("[aé]"), ParseError::NonAscii { index: 2 });
+        assert_eq!(error("[0-9z-a]"), ParseError::BackwardsRange { index: 4 });
+        assert_eq!(error("[x[:word:]]"), ParseError::UnknownClass { index: 2 });
+        assert_eq!(error("[[:alpha]"), ParseError::UnknownClass { index: 1 });
+        assert_eq!(
+            error("[[.space.]]"),
+            ParseError::InvalidCollatingElement { index: 1 }
+        );
+        assert_eq!(
+            error("[a-[.b]"),
+            ParseError::InvalidCollatingElement { index: 3 }
+        );
+    }
+
+    #[test]
+    fn display() {
+        let display = |expression| ByteSet::parse(expression).unwrap().to_string();
+        assert_eq!(display("[[:alnum:]_.-]"), "[.0-9A-Z_a-z-]");
+        assert_eq!(display("[ab]"), "[ab]");
+        assert_eq!(display("[a-c]"), "[a-c]");
+        assert_eq!(display("[^a]"), "[^a]");
+        assert_eq!(display("[^^]"), "[^^]");
+        assert_eq!(display("[[=^=]]"), "[[.^.]]");
+        assert_eq!(display("[-^]"), "[[.^.]-]");
+        assert_eq!(display("[]Z-a-]"), "[]Z[\\^_-a-]");
+        assert_eq!(display("[^]a-]"), "[^]a-]");
+        assert_eq!(ByteSet::EMPTY.to_string(), "[]");
+        assert_eq!(ByteSet::ALL.to_string(), "[^]");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100_000 {
+            // Sparse and dense sets, so that there are both lone bytes and long runs.
+            let ascii = match rng.gen_range(0..3) {
+                0 => rng.gen::<u128>() & rng.gen::<u128>(),
+                1 => rng.gen::<u128>(),
+                _ => rng.gen::<u128>() | rng.gen::<u128>(),
+            };
+            let set = ByteSet {
+                ascii,
+                high: rng.gen(),
+            };
+            if set == ByteSet::EMPTY || set == ByteSet::ALL {
+                continue;
+            }
+            let text = set.to_string();
+            assert_eq!(text.parse(), Ok(set), "Failed on {:?}", text);
+        }
+        for x in 0..128 {
+            for high in [false, true] {
+                let set = ByteSet {
+                    ascii: 1 << x,
+                    high,
+                };
+                assert_eq!(set.to_string().parse(), Ok(set), "Failed on {}", x);
+                assert_eq!((!set).to_string().parse(), Ok(!set), "Failed on {}", x);
+            }
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let word: ByteSet = "[[:alnum:]_]".parse().unwrap();
+        let not_word: ByteSet = "[^[:alnum:]_]".parse().unwrap();
+        assert_eq!(!word, not_word);
+        assert_eq!(word | not_word, ByteSet::ALL);
+        assert!((word & not_word).is_empty());
+        assert!(not_word.contains_high());
+        assert!(word.contains_all(b"is_ascii_2"));
+        assert!(!word.contains_char('\u{E9}'));
+        assert!(not_word.contains_char('\u{E9}'));
+        assert!(!not_word.contains_char('\u{100}'));
+        assert_eq!(word.ascii_bits().count_ones(), 63);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::IsAscii2;
+
+/// A set of bytes that can be tested for membership.
+///
+/// Classes are normally zero-sized types whose membership test is one of the
+/// branchless techniques used by `IsAscii2`, so generic code over `AsciiClass`
+/// compiles down to the same instructions as a hand-written predicate.
+pub trait AsciiClass {
+    /// Returns `true` iff `x` is in this class.
+    fn contains(&self, x: u8) -> bool;
+
+    /// Returns `true` iff `x` is in this class. A `char` is treated as the byte with
+    /// the same codepoint, so `char`s above `'\u{FF}'` are never in a class.
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        u8::try_from(x).is_ok_and(|x| self.contains(x))
+    }
+
+    /// Tests the eight bytes of `word` at once. The bytes are in little-endian order,
+    /// so the first byte of a slice read with `u64::from_le_bytes` is the lowest byte.
+    /// Returns a word with the high bit of each byte set iff that byte is in this
+    /// class, and every other bit clear.
+    ///
+    /// The default tests one byte at a time. Classes with a branchless whole-word test
+    /// override it.
+    #[inline]
+    fn word_matches(&self, word: u64) -> u64 {
+        let mut matches = 0;
+        for (i, x) in word.to_le_bytes().into_iter().enumerate() {
+            matches |= (self.contains(x) as u64) << (i * 8 + 7);
+        }
+        matches
+    }
+
+    /// Returns the index of the first byte of `bytes` that is not in this class, or
+    /// `None` if every byte is in this class.
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        bytes.iter().position(|&x| !self.contains(x))
+    }
+
+    /// Returns `true` iff every byte of `bytes` is in this class.
+    #[inline]
+    fn contains_all(&self, bytes: &[u8]) -> bool {
+        self.find_nonmember(bytes).is_none()
+    }
+}
+
+impl<C: AsciiClass + ?Sized> AsciiClass for &C {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        (**self).contains(x)
+    }
+
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        (**self).contains_char(x)
+    }
+
+    #[inline]
+    fn word_matches(&self, word: u64) -> u64 {
+        (**self).word_matches(word)
+    }
+
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        (**self).find_nonmember(bytes)
+    }
+
+    #[inline]
+    fn contains_all(&self, bytes: &[u8]) -> bool {
+        (**self).contains_all(bytes)
+    }
+}
+
+/// Builds a 128-bit membership bitmap from inclusive codepoint ranges. The bit index
+/// corresponds to the codepoint, so a byte `x` is in the set iff `x < 128` and bit
+/// index `x` of the bitmap is 1. This is the 33-bit shift mask used by
+/// `is_ascii_whitespace_2`, widened to cover all of ASCII.
+pub(crate) const fn ascii_bitmap(ranges: &[(u8, u8)]) -> u128 {
+    let mut bitmap = 0;
+    let mut i = 0;
+    while i < ranges.len() {
+        let (start, end) = ranges[i];
+        assert!(start <= end && end < 128, "bitmap ranges must be ASCII");
+        let mut x = start;
+        while x <= end {
+            bitmap |= 1 << x;
+            x += 1;
+        }
+        i += 1;
+    }
+    bitmap
+}
+
+/// Tests membership of `x` in a bitmap built by `ascii_bitmap`. The `x < 128` check
+/// comes first so that the shift never overflows.
+#[inline]
+pub(crate) const fn in_ascii_bitmap(bitmap: u128, x: u32) -> bool {
+    x < 128 && ((bitmap >> x) & 1) != 0
+}
+
+// Defines a zero-sized class type for each method of an `IsAscii2`-like trait that
+// is implemented for both `u8` and `char`. A class may also name, in brackets, a
+// branchless `word_matches`, which `find_nonmember` then uses to scan eight bytes at a
+// time.
+macro_rules! class_types {
+    ($($(#[$attr: meta])* $name: ident => $method: ident $([$word_matches: path])?,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl $crate::class::AsciiClass for $name {
+                #[inline]
+                fn contains(&self, x: u8) -> bool {
+                    x.$method()
+                }
+
+                #[inline]
+                fn contains_char(&self, x: char) -> bool {
+                    x.$method()
+                }
+
+                $(
+                    #[inline]
+                    fn word_matches(&self, word: u64) -> u64 {
+                        $word_matches(word)
+                    }
+
+                    #[inline]
+                    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+                        $crate::swar::position(
+                            bytes,
+                            |word| !$word_matches(word) & $crate::swar::HIGH_BITS,
+                            |x| !x.$method(),
+                        )
+                    }
+                )?
+            }
+        )*
+    };
+}
+
+pub(crate) use class_types;
+
+// The `swar` masks of the `IsAscii2` classes, built from the same ranges as the
+// byte-at-a-time versions.
+pub(crate) mod words {
+    use crate::swar::{eq, in_range, lt, splat, HIGH_BITS};
+
+    pub(crate) fn ascii(word: u64) -> u64 {
+        !word & HIGH_BITS
+    }
+
+    pub(crate) fn alphabetic(word: u64) -> u64 {
+        // The same `| 0b0010_0000` case fold as `is_ascii_alphabetic_2`.
+        in_range(word | splat(0b0010_0000), b'a', 26)
+    }
+
+    pub(crate) fn alphanumeric(word: u64) -> u64 {
+        alphabetic(word) | digit(word)
+    }
+
+    pub(crate) fn control(word: u64) -> u64 {
+        lt(word, b' ') | eq(word, 127)
+    }
+
+    pub(crate) fn digit(word: u64) -> u64 {
+        in_range(word, b'0', 10)
+    }
+
+    pub(crate) fn graphic(word: u64) -> u64 {
+        in_range(word, b'!', 94)
+    }
+
+    pub(crate) fn hexdigit(word: u64) -> u64 {
+        digit(word) | in_range(word | splat(0b0010_0000), b'a', 6)
+    }
+
+    pub(crate) fn lowercase(word: u64) -> u64 {
+        in_range(word, b'a', 26)
+    }
+
+    pub(crate) fn punctuation(word: u64) -> u64 {
+        graphic(word) & !alphanumeric(word)
+    }
+
+    pub(crate) fn uppercase(word: u64) -> u64 {
+        in_range(word, b'A', 26)
+    }
+
+    pub(crate) fn whitespace(word: u64) -> u64 {
+        eq(word, b' ') | in_range(word, b'\t', 2) | in_range(word, b'\x0C', 2)
+    }
+}
+
+class_types! {
+    /// The class of `is_ascii_2`.
+    Ascii => is_ascii_2 [words::ascii],
+    /// The class of `is_ascii_alphabetic_2`.
+    AsciiAlphabetic => is_ascii_alphabetic_2 [words::alphabetic],
+    /// The class of `is_ascii_alphanumeric_2`.
+    AsciiAlphanumeric => is_ascii_alphanumeric_2 [words::alphanumeric],
+    /// The class of `is_ascii_control_2`.
+    AsciiControl => is_ascii_control_2 [words::control],
+    /// The class of `is_ascii_digit_2`.
+    AsciiDigit => is_ascii_digit_2 [words::digit],
+    /// The class of `is_ascii_graphic_2`.
+    AsciiGraphic => is_ascii_graphic_2 [words::graphic],
+    /// The class of `is_ascii_hexdigit_2`.
+    AsciiHexdigit => is_ascii_hexdigit_2 [words::hexdigit],
+    /// The class of `is_ascii_lowercase_2`.
+    AsciiLowercase => is_ascii_lowercase_2 [words::lowercase],
+    /// The class of `is_ascii_punctuation_2`.
+    AsciiPunctuation => is_ascii_punctuation_2 [words::punctuation],
+    /// The class of `is_ascii_uppercase_2`.
+    AsciiUppercase => is_ascii_uppercase_2 [words::uppercase],
+    /// The class of `is_ascii_whitespace_2`.
+    AsciiWhitespace => is_ascii_whitespace_2 [words::whitespace],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks the word-at-a-time `find_nonmember` against the default one for every
+    // byte value at every offset of inputs shorter and longer than a word.
+    fn check(class: impl AsciiClass) {
+        for x in 0..=u8::MAX {
+            let filler = (0..=u8::MAX).find(|&y| class.contains(y)).unwrap();
+            for len in 1..=24 {
+                for index in 0..len {
+                    let mut bytes = [filler; 24];
+                    bytes[index] = x;
+                    let bytes = &bytes[..len];
+                    assert_eq!(
+                        class.find_nonmember(bytes),
+                        bytes.iter().position(|&y| !class.contains(y)),
+                        "Failed on {}",
+                        x
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn find_nonmember_matches_contains() {
+        check(Ascii);
+        check(AsciiAlphabetic);
+        check(AsciiAlphanumeric);
+        check(AsciiControl);
+        check(AsciiDigit);
+        check(AsciiGraphic);
+        check(AsciiHexdigit);
+        check(AsciiLowercase);
+        check(AsciiPunctuation);
+        check(AsciiUppercase);
+        check(AsciiWhitespace);
+    }
+
+    #[test]
+    fn contains_matches_is_ascii_2() {
+        for x in 0..=u8::MAX {
+            assert!(
+                AsciiHexdigit.contains(x) == x.is_ascii_hexdigit_2(),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                AsciiPunctuation.contains_char(x as char) == (x as char).is_ascii_punctuation_2(),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use core::fmt;
+
+/// How `=` padding is treated by the base-N codecs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// Encoding emits padding. Decoding requires the input to be padded to a whole
+    /// number of blocks.
+    Strict,
+    /// Encoding emits no padding. Decoding accepts both padded and unpadded input,
+    /// but any padding that is present must be correct.
+    Optional,
+}
+
+/// An error from encoding into a caller-provided buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EncodeError {
+    /// The output buffer is shorter than the `required` number of bytes.
+    OutputTooSmall { required: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EncodeError::OutputTooSmall { required } => {
+                write!(f, "output buffer too small: {} bytes required", required)
+            }
+        }
+    }
+}
+
+/// An error from decoding into a caller-provided buffer. Indexes are byte offsets
+/// into the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// `byte` at `index` is not in the alphabet.
+    InvalidByte { index: usize, byte: u8 },
+    /// The input length cannot be produced by the encoder.
+    InvalidLength,
+    /// Padding is missing, misplaced, or of the wrong length. `index` is where the
+    /// problem was detected.
+    InvalidPadding { index: usize },
+    /// The symbol at `index` has nonzero bits that fall past the end of the data,
+    /// so the input is not the canonical encoding of any byte string.
+    TrailingBits { index: usize },
+    /// The output buffer is shorter than the `required` number of bytes.
+    OutputTooSmall { required: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidByte { index, byte } => {
+                write!(f, "invalid byte {:#04x} at index {}", byte, index)
+            }
+            DecodeError::InvalidLength => f.write_str("invalid input length"),
+            DecodeError::InvalidPadding { index } => {
+                write!(f, "invalid padding at index {}", index)
+            }
+            DecodeError::TrailingBits { index } => {
+                write!(f, "nonzero trailing bits at index {}", index)
+            }
+            DecodeError::OutputTooSmall { required } => {
+                write!(f, "output buffer too small: {} bytes required", required)
+            }
+        }
+    }
+}
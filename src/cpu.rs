@@ -0,0 +1,71 @@
+// Runtime detection of the x86-64 vector extensions that `shuffle` and `filter` use.
+// `cpuid` needs no `std`, so this works without it. Detection runs on the first call
+// and is cached for the rest.
+
+/// The widest vector extension the CPU supports. Each level includes the ones below
+/// it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Level {
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Ssse3,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+#[inline]
+pub(crate) fn level() -> Level {
+    Level::Scalar
+}
+
+/// Detects the level on the first call, and caches it.
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub(crate) fn level() -> Level {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    // 0 until detection has run, and then 1 more than the `Level`.
+    static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+    const LEVELS: [Level; 3] = [Level::Scalar, Level::Ssse3, Level::Avx2];
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => {
+            let level = detect();
+            // Racing threads all detect the same level, so any of them can store it.
+            LEVEL.store(level as u8 + 1, Ordering::Relaxed);
+            level
+        }
+        cached => LEVELS[cached as usize - 1],
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn detect() -> Level {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    if cfg!(target_feature = "avx2") {
+        return Level::Avx2;
+    }
+
+    let leaf_1 = __cpuid(1);
+    let ssse3 = cfg!(target_feature = "ssse3") || leaf_1.ecx & (1 << 9) != 0;
+    let osxsave = leaf_1.ecx & (1 << 27) != 0;
+    let avx = leaf_1.ecx & (1 << 28) != 0;
+
+    // AVX2 also needs the OS to save the upper halves of the `ymm` registers, which
+    // it says in `xcr0` when `osxsave` is set.
+    // SAFETY: `osxsave` means `xgetbv` is supported and enabled.
+    let ymm_saved = osxsave && avx && unsafe { _xgetbv(0) } & 0b110 == 0b110;
+    // Leaf 0 gives the highest leaf, and leaf 7 is only read if it exists.
+    let avx2 = ymm_saved && __cpuid(0).eax >= 7 && __cpuid_count(7, 0).ebx & (1 << 5) != 0;
+
+    if avx2 {
+        Level::Avx2
+    } else if ssse3 {
+        Level::Ssse3
+    } else {
+        Level::Scalar
+    }
+}
@@ -0,0 +1,149 @@
+//! `extern "C"` exports of every class, for C and C++ callers. The
+//! `is_ascii_speedups_ffi` package builds these into a `cdylib` and a `staticlib`,
+//! and carries the matching C header.
+//!
+//! Each class `X` gets three functions:
+//!
+//! - `bool ias_is_X(uint8_t x)`, whether `x` is in the class.
+//! - `bool ias_all_X(const uint8_t *bytes, size_t len)`, whether every byte is in
+//!   the class.
+//! - `size_t ias_find_not_X(const uint8_t *bytes, size_t len)`, the index of the
+//!   first byte that is not in the class, or `len` if there is none.
+
+use core::slice;
+
+use crate::base32::{Base32Crockford, Base32Standard};
+use crate::base58::Base58;
+use crate::base64::{Base64Standard, Base64UrlSafe};
+use crate::class::*;
+use crate::http::{HttpFieldValueByte, HttpTchar};
+use crate::ident::{
+    CIdentContinue, CIdentStart, JsIdentContinue, JsIdentStart, RustIdentContinue, RustIdentStart,
+    SqlIdentContinue, SqlIdentStart,
+};
+use crate::uri::{UriGenDelim, UriPchar, UriSubDelim, UriUnreserved};
+use crate::whitespace::{Blank, CSpace, JsonWhitespace, XmlWhitespace};
+
+/// The names of the functions exported for one class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Export {
+    /// The Rust type of the class.
+    pub class: &'static str,
+    pub is: &'static str,
+    pub all: &'static str,
+    pub find_not: &'static str,
+}
+
+// A null pointer is allowed when `len` is 0, as is common in C APIs, but not by
+// `slice::from_raw_parts`.
+unsafe fn bytes<'a>(bytes: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes, len)
+    }
+}
+
+macro_rules! exports {
+    ($($class: ident => $is: ident, $all: ident, $find_not: ident;)*) => {
+        $(
+            #[doc = concat!("Whether `x` is in `", stringify!($class), "`.")]
+            #[no_mangle]
+            pub extern "C" fn $is(x: u8) -> bool {
+                $class.contains(x)
+            }
+
+            #[doc = concat!("Whether every byte is in `", stringify!($class), "`.")]
+            ///
+            /// # Safety
+            ///
+            /// `bytes` must point to `len` readable bytes, or `len` must be 0.
+            #[no_mangle]
+            pub unsafe extern "C" fn $all(bytes: *const u8, len: usize) -> bool {
+                $class.contains_all(self::bytes(bytes, len))
+            }
+
+            #[doc = concat!(
+                "The index of the first byte that is not in `",
+                stringify!($class),
+                "`, or `len` if every byte is."
+            )]
+            ///
+            /// # Safety
+            ///
+            /// `bytes` must point to `len` readable bytes, or `len` must be 0.
+            #[no_mangle]
+            pub unsafe extern "C" fn $find_not(bytes: *const u8, len: usize) -> usize {
+                $class.find_nonmember(self::bytes(bytes, len)).unwrap_or(len)
+            }
+        )*
+
+        /// Every exported function, in the order they appear in the C header.
+        pub const EXPORTS: &[Export] = &[
+            $(
+                Export {
+                    class: stringify!($class),
+                    is: stringify!($is),
+                    all: stringify!($all),
+                    find_not: stringify!($find_not),
+                },
+            )*
+        ];
+    };
+}
+
+exports! {
+    Ascii => ias_is_ascii, ias_all_ascii, ias_find_not_ascii;
+    AsciiAlphabetic =>
+        ias_is_ascii_alphabetic, ias_all_ascii_alphabetic, ias_find_not_ascii_alphabetic;
+    AsciiAlphanumeric =>
+        ias_is_ascii_alphanumeric, ias_all_ascii_alphanumeric, ias_find_not_ascii_alphanumeric;
+    AsciiControl => ias_is_ascii_control, ias_all_ascii_control, ias_find_not_ascii_control;
+    AsciiDigit => ias_is_ascii_digit, ias_all_ascii_digit, ias_find_not_ascii_digit;
+    AsciiGraphic => ias_is_ascii_graphic, ias_all_ascii_graphic, ias_find_not_ascii_graphic;
+    AsciiHexdigit =>
+        ias_is_ascii_hexdigit, ias_all_ascii_hexdigit, ias_find_not_ascii_hexdigit;
+    AsciiLowercase =>
+        ias_is_ascii_lowercase, ias_all_ascii_lowercase, ias_find_not_ascii_lowercase;
+    AsciiPunctuation =>
+        ias_is_ascii_punctuation, ias_all_ascii_punctuation, ias_find_not_ascii_punctuation;
+    AsciiUppercase =>
+        ias_is_ascii_uppercase, ias_all_ascii_uppercase, ias_find_not_ascii_uppercase;
+    AsciiWhitespace =>
+        ias_is_ascii_whitespace, ias_all_ascii_whitespace, ias_find_not_ascii_whitespace;
+    CSpace => ias_is_c_space, ias_all_c_space, ias_find_not_c_space;
+    JsonWhitespace =>
+        ias_is_json_whitespace, ias_all_json_whitespace, ias_find_not_json_whitespace;
+    XmlWhitespace => ias_is_xml_whitespace, ias_all_xml_whitespace, ias_find_not_xml_whitespace;
+    Blank => ias_is_blank, ias_all_blank, ias_find_not_blank;
+    UriUnreserved => ias_is_uri_unreserved, ias_all_uri_unreserved, ias_find_not_uri_unreserved;
+    UriSubDelim => ias_is_uri_sub_delim, ias_all_uri_sub_delim, ias_find_not_uri_sub_delim;
+    UriGenDelim => ias_is_uri_gen_delim, ias_all_uri_gen_delim, ias_find_not_uri_gen_delim;
+    UriPchar => ias_is_uri_pchar, ias_all_uri_pchar, ias_find_not_uri_pchar;
+    HttpTchar => ias_is_http_tchar, ias_all_http_tchar, ias_find_not_http_tchar;
+    HttpFieldValueByte =>
+        ias_is_http_field_value_byte,
+        ias_all_http_field_value_byte,
+        ias_find_not_http_field_value_byte;
+    RustIdentStart =>
+        ias_is_rust_ident_start, ias_all_rust_ident_start, ias_find_not_rust_ident_start;
+    RustIdentContinue =>
+        ias_is_rust_ident_continue, ias_all_rust_ident_continue, ias_find_not_rust_ident_continue;
+    CIdentStart => ias_is_c_ident_start, ias_all_c_ident_start, ias_find_not_c_ident_start;
+    CIdentContinue =>
+        ias_is_c_ident_continue, ias_all_c_ident_continue, ias_find_not_c_ident_continue;
+    JsIdentStart => ias_is_js_ident_start, ias_all_js_ident_start, ias_find_not_js_ident_start;
+    JsIdentContinue =>
+        ias_is_js_ident_continue, ias_all_js_ident_continue, ias_find_not_js_ident_continue;
+    SqlIdentStart => ias_is_sql_ident_start, ias_all_sql_ident_start, ias_find_not_sql_ident_start;
+    SqlIdentContinue =>
+        ias_is_sql_ident_continue, ias_all_sql_ident_continue, ias_find_not_sql_ident_continue;
+    Base64Standard =>
+        ias_is_base64_standard, ias_all_base64_standard, ias_find_not_base64_standard;
+    Base64UrlSafe => ias_is_base64_url_safe, ias_all_base64_url_safe, ias_find_not_base64_url_safe;
+    Base32Standard =>
+        ias_is_base32_standard, ias_all_base32_standard, ias_find_not_base32_standard;
+    Base32Crockford =>
+        ias_is_base32_crockford, ias_all_base32_crockford, ias_find_not_base32_crockford;
+    Base58 => ias_is_base58, ias_all_base58, ias_find_not_base58;
+}
@@ -0,0 +1,465 @@
+//! Keeping only the bytes of a class, such as stripping everything but the
+//! alphanumerics from a key before hashing it.
+//!
+//! The bytes are handled a word at a time: the class's `word_matches` mask says which
+//! bytes to keep, and the kept bytes are moved to the bottom of the word. With SSSE3,
+//! which is detected at runtime, that move is a single `pshufb` driven by a
+//! 256-entry table of shuffles. Otherwise it's a loop over the kept bytes. Words that
+//! are kept whole or dropped whole skip the move entirely.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::class::*;
+use crate::cpu;
+use crate::swar;
+use crate::AsciiClass;
+
+/// Moves the bytes of `word` whose bits are set in `keep` to the bottom of the word,
+/// in order. The other bytes of the result are unspecified.
+#[inline]
+fn compress_scalar(word: u64, keep: u8) -> u64 {
+    let mut keep = keep;
+    let mut compressed = 0;
+    let mut len = 0;
+    while keep != 0 {
+        let i = keep.trailing_zeros();
+        compressed |= ((word >> (i * 8)) & 0xFF) << (len * 8);
+        len += 1;
+        // Clear the lowest set bit.
+        keep &= keep - 1;
+    }
+    compressed
+}
+
+/// `compress_scalar` with one `pshufb`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+unsafe fn compress_ssse3(word: u64, keep: u8) -> u64 {
+    use core::arch::x86_64::{_mm_cvtsi128_si64, _mm_cvtsi64_si128, _mm_shuffle_epi8};
+
+    // `SHUFFLES[keep]` holds, in its low bytes, the index of each kept byte.
+    const SHUFFLES: [u64; 256] = {
+        let mut shuffles = [0; 256];
+        let mut keep = 0;
+        while keep < 256 {
+            let mut shuffle = 0;
+            let mut len = 0;
+            let mut i = 0;
+            while i < 8 {
+                if keep & (1 << i) != 0 {
+                    shuffle |= (i as u64) << (len * 8);
+                    len += 1;
+                }
+                i += 1;
+            }
+            shuffles[keep] = shuffle;
+            keep += 1;
+        }
+        shuffles
+    };
+
+    let shuffled = _mm_shuffle_epi8(
+        _mm_cvtsi64_si128(word as i64),
+        _mm_cvtsi64_si128(SHUFFLES[keep as usize] as i64),
+    );
+    _mm_cvtsi128_si64(shuffled) as u64
+}
+
+/// Writes the first `len` bytes of `word` to `dst` at `at`. Writes all eight bytes if
+/// there's room, since that's cheaper than a variable-length copy, and the bytes past
+/// `len` will be overwritten or are past the end of the result.
+#[inline]
+fn write_word(dst: &mut [u8], at: usize, word: u64, len: usize) {
+    match dst.get_mut(at..at + 8) {
+        Some(dst) => dst.copy_from_slice(&word.to_le_bytes()),
+        None => dst[at..at + len].copy_from_slice(&word.to_le_bytes()[..len]),
+    }
+}
+
+/// Writes the bytes of `word` that are in `class` to `dst` at `write`, returning how
+/// many were written.
+#[inline(always)]
+fn filter_word<C: AsciiClass>(
+    class: &C,
+    word: u64,
+    dst: &mut [u8],
+    write: usize,
+    compress: impl Fn(u64, u8) -> u64,
+) -> usize {
+    let keep = swar::pack(class.word_matches(word)) as u8;
+    match keep {
+        0 => 0,
+        0xFF => {
+            write_word(dst, write, word, 8);
+            8
+        }
+        _ => {
+            let len = keep.count_ones() as usize;
+            write_word(dst, write, compress(word, keep), len);
+            len
+        }
+    }
+}
+
+// Writes the bytes of `bytes` that are in `class` to the start of `dst`, in order,
+// returning how many there were.
+#[inline]
+fn filter_into<C: AsciiClass>(class: &C, bytes: &[u8], dst: &mut [u8]) -> usize {
+    match cpu::level() {
+        // SAFETY: `level` only returns a level the CPU supports.
+        #[cfg(target_arch = "x86_64")]
+        cpu::Level::Ssse3 | cpu::Level::Avx2 => unsafe { filter_into_ssse3(class, bytes, dst) },
+        _ => filter_into_with(class, bytes, dst, compress_scalar),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn filter_into_ssse3<C: AsciiClass>(class: &C, bytes: &[u8], dst: &mut [u8]) -> usize {
+    filter_into_with(class, bytes, dst, |word, keep| compress_ssse3(word, keep))
+}
+
+#[inline(always)]
+fn filter_into_with<C: AsciiClass>(
+    class: &C,
+    bytes: &[u8],
+    dst: &mut [u8],
+    compress: impl Fn(u64, u8) -> u64,
+) -> usize {
+    let mut write = 0;
+    let mut words = bytes.chunks_exact(8);
+    for word in &mut words {
+        write += filter_word(class, swar::load(word), dst, write, &compress);
+    }
+    for &x in words.remainder() {
+        if class.contains(x) {
+            dst[write] = x;
+            write += 1;
+        }
+    }
+    write
+}
+
+// Keeps the bytes of `bytes` that are in `class`, moving them to the front. Returns
+// how many were kept.
+#[cfg(feature = "alloc")]
+#[inline]
+fn filter_in_place<C: AsciiClass>(class: &C, bytes: &mut [u8]) -> usize {
+    match cpu::level() {
+        // SAFETY: `level` only returns a level the CPU supports.
+        #[cfg(target_arch = "x86_64")]
+        cpu::Level::Ssse3 | cpu::Level::Avx2 => unsafe { filter_in_place_ssse3(class, bytes) },
+        _ => filter_in_place_with(class, bytes, compress_scalar),
+    }
+}
+
+#[cfg(all(feature = "alloc", target_arch = "x86_64"))]
+#[target_feature(enable = "ssse3")]
+unsafe fn filter_in_place_ssse3<C: AsciiClass>(class: &C, bytes: &mut [u8]) -> usize {
+    filter_in_place_with(class, bytes, |word, keep| compress_ssse3(word, keep))
+}
+
+#[cfg(feature = "alloc")]
+#[inline(always)]
+fn filter_in_place_with<C: AsciiClass>(
+    class: &C,
+    bytes: &mut [u8],
+    compress: impl Fn(u64, u8) -> u64,
+) -> usize {
+    let mut write = 0;
+    let mut read = 0;
+    while read + 8 <= bytes.len() {
+        // The word is copied out before anything is written, and the write position
+        // never passes the read position, so no unread byte is overwritten.
+        let word = swar::load(&bytes[read..]);
+        write += filter_word(class, word, &mut bytes[..read + 8], write, &compress);
+        read += 8;
+    }
+    while read < bytes.len() {
+        let x = bytes[read];
+        if class.contains(x) {
+            bytes[write] = x;
+            write += 1;
+        }
+        read += 1;
+    }
+    write
+}
+
+pub trait FilterAscii2 {
+    /// Writes the bytes of `self` that are in `class` to the start of `dst`, in order,
+    /// and returns how many there were. The rest of `dst` may also be overwritten.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is shorter than the number of bytes written.
+    fn filter_class_into_2<C: AsciiClass>(&self, class: C, dst: &mut [u8]) -> usize;
+
+    #[inline]
+    fn filter_ascii_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(Ascii, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_alphabetic_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiAlphabetic, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_alphanumeric_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiAlphanumeric, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_control_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiControl, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_digit_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiDigit, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_graphic_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiGraphic, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_hexdigit_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiHexdigit, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_lowercase_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiLowercase, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_punctuation_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiPunctuation, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_uppercase_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiUppercase, dst)
+    }
+
+    #[inline]
+    fn filter_ascii_whitespace_into_2(&self, dst: &mut [u8]) -> usize {
+        self.filter_class_into_2(AsciiWhitespace, dst)
+    }
+}
+
+impl FilterAscii2 for [u8] {
+    #[inline]
+    fn filter_class_into_2<C: AsciiClass>(&self, class: C, dst: &mut [u8]) -> usize {
+        filter_into(&class, self, dst)
+    }
+}
+
+/// `Vec::retain` for classes. Only available with the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub trait RetainAscii2 {
+    /// Removes the bytes that aren't in `class`.
+    fn retain_class_2<C: AsciiClass>(&mut self, class: C);
+
+    #[inline]
+    fn retain_ascii_2(&mut self) {
+        self.retain_class_2(Ascii)
+    }
+
+    #[inline]
+    fn retain_ascii_alphabetic_2(&mut self) {
+        self.retain_class_2(AsciiAlphabetic)
+    }
+
+    #[inline]
+    fn retain_ascii_alphanumeric_2(&mut self) {
+        self.retain_class_2(AsciiAlphanumeric)
+    }
+
+    #[inline]
+    fn retain_ascii_control_2(&mut self) {
+        self.retain_class_2(AsciiControl)
+    }
+
+    #[inline]
+    fn retain_ascii_digit_2(&mut self) {
+        self.retain_class_2(AsciiDigit)
+    }
+
+    #[inline]
+    fn retain_ascii_graphic_2(&mut self) {
+        self.retain_class_2(AsciiGraphic)
+    }
+
+    #[inline]
+    fn retain_ascii_hexdigit_2(&mut self) {
+        self.retain_class_2(AsciiHexdigit)
+    }
+
+    #[inline]
+    fn retain_ascii_lowercase_2(&mut self) {
+        self.retain_class_2(AsciiLowercase)
+    }
+
+    #[inline]
+    fn retain_ascii_punctuation_2(&mut self) {
+        self.retain_class_2(AsciiPunctuation)
+    }
+
+    #[inline]
+    fn retain_ascii_uppercase_2(&mut self) {
+        self.retain_class_2(AsciiUppercase)
+    }
+
+    #[inline]
+    fn retain_ascii_whitespace_2(&mut self) {
+        self.retain_class_2(AsciiWhitespace)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl RetainAscii2 for Vec<u8> {
+    #[inline]
+    fn retain_class_2<C: AsciiClass>(&mut self, class: C) {
+        let len = filter_in_place(&class, self);
+        self.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::IsAscii2;
+    use alloc::vec::Vec;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_bytes(rng: &mut StdRng) -> Vec<u8> {
+        let len = rng.gen_range(0..100);
+        // Half of the inputs are ASCII, which most classes are dense in.
+        let max = if rng.gen() { 0x7F } else { 0xFF };
+        (0..len).map(|_| rng.gen_range(0..=max)).collect()
+    }
+
+    // Every backend this CPU can run.
+    fn compressors() -> Vec<fn(u64, u8) -> u64> {
+        let mut compressors: Vec<fn(u64, u8) -> u64> = alloc::vec![compress_scalar];
+        #[cfg(target_arch = "x86_64")]
+        if cpu::level() >= cpu::Level::Ssse3 {
+            compressors.push(|word, keep| unsafe { compress_ssse3(word, keep) });
+        }
+        compressors
+    }
+
+    #[test]
+    fn compress_every_mask() {
+        let word = u64::from_le_bytes(*b"01234567");
+        for compress in compressors() {
+            for keep in 0..=u8::MAX {
+                let expected: Vec<u8> = (0..8)
+                    .filter(|i| keep & (1 << i) != 0)
+                    .map(|i| b'0' + i)
+                    .collect();
+                let compressed = compress(word, keep).to_le_bytes();
+                assert_eq!(
+                    &compressed[..expected.len()],
+                    &expected[..],
+                    "Failed on {}",
+                    keep
+                );
+            }
+        }
+    }
+
+    // The tests below go through `cpu::level`, so this checks every backend the same
+    // way.
+    #[test]
+    fn every_backend() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let bytes = random_bytes(&mut rng);
+            let mut expected = bytes.clone();
+            expected.retain(|x| x.is_ascii_alphanumeric_2());
+            for compress in compressors() {
+                let mut dst = alloc::vec![0; expected.len()];
+                let len = filter_into_with(&AsciiAlphanumeric, &bytes, &mut dst, compress);
+                assert_eq!(&dst[..len], &expected[..], "Failed on {:?}", bytes);
+
+                #[cfg(feature = "alloc")]
+                {
+                    let mut retained = bytes.clone();
+                    let len = filter_in_place_with(&AsciiAlphanumeric, &mut retained, compress);
+                    assert_eq!(&retained[..len], &expected[..], "Failed on {:?}", bytes);
+                }
+            }
+        }
+    }
+
+    macro_rules! against_retain {
+        ($($test: ident: $filter: ident, $retain: ident == $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    let mut rng = StdRng::seed_from_u64(0);
+                    for _ in 0..1000 {
+                        let bytes = random_bytes(&mut rng);
+                        let mut expected = bytes.clone();
+                        expected.retain(|x| x.$method());
+
+                        // Exactly enough room, and plenty of room.
+                        let mut dst = alloc::vec![0; expected.len()];
+                        assert_eq!(bytes.$filter(&mut dst), expected.len());
+                        assert_eq!(dst, expected, "Failed on {:?}", bytes);
+                        let mut dst = alloc::vec![0; bytes.len() + 8];
+                        let len = bytes.$filter(&mut dst);
+                        assert_eq!(&dst[..len], &expected[..], "Failed on {:?}", bytes);
+
+                        #[cfg(feature = "alloc")]
+                        {
+                            let mut retained = bytes.clone();
+                            retained.$retain();
+                            assert_eq!(retained, expected, "Failed on {:?}", bytes);
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    against_retain! {
+        ascii: filter_ascii_into_2, retain_ascii_2 == is_ascii_2,
+        alphabetic: filter_ascii_alphabetic_into_2, retain_ascii_alphabetic_2 == is_ascii_alphabetic_2,
+        alphanumeric: filter_ascii_alphanumeric_into_2, retain_ascii_alphanumeric_2 == is_ascii_alphanumeric_2,
+        control: filter_ascii_control_into_2, retain_ascii_control_2 == is_ascii_control_2,
+        digit: filter_ascii_digit_into_2, retain_ascii_digit_2 == is_ascii_digit_2,
+        graphic: filter_ascii_graphic_into_2, retain_ascii_graphic_2 == is_ascii_graphic_2,
+        hexdigit: filter_ascii_hexdigit_into_2, retain_ascii_hexdigit_2 == is_ascii_hexdigit_2,
+        lowercase: filter_ascii_lowercase_into_2, retain_ascii_lowercase_2 == is_ascii_lowercase_2,
+        punctuation: filter_ascii_punctuation_into_2, retain_ascii_punctuation_2 == is_ascii_punctuation_2,
+        uppercase: filter_ascii_uppercase_into_2, retain_ascii_uppercase_2 == is_ascii_uppercase_2,
+        whitespace: filter_ascii_whitespace_into_2, retain_ascii_whitespace_2 == is_ascii_whitespace_2,
+    }
+
+    #[test]
+    fn custom_class() {
+        use crate::uri::UriUnreserved;
+
+        let mut dst = [0; 32];
+        let len = b"Hello, World! ~_-.".filter_class_into_2(UriUnreserved, &mut dst);
+        assert_eq!(&dst[..len], b"HelloWorld~_-.");
+    }
+
+    #[test]
+    #[should_panic]
+    fn dst_too_short() {
+        let mut dst = [0; 3];
+        b"a1b2c3d4".filter_ascii_digit_into_2(&mut dst);
+    }
+}
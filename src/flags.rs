@@ -0,0 +1,177 @@
+//! Every `IsAscii2` class of a byte at once, as a set of flags, for code that needs
+//! more than one class of the same byte.
+
+use core::ops::{BitAnd, BitOr, Not};
+
+use crate::IsAscii2;
+
+/// A set of `IsAscii2` classes, one bit per class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AsciiClasses(u16);
+
+impl AsciiClasses {
+    pub const NONE: Self = AsciiClasses(0);
+    pub const ASCII: Self = AsciiClasses(1 << 0);
+    pub const ALPHABETIC: Self = AsciiClasses(1 << 1);
+    pub const ALPHANUMERIC: Self = AsciiClasses(1 << 2);
+    pub const CONTROL: Self = AsciiClasses(1 << 3);
+    pub const DIGIT: Self = AsciiClasses(1 << 4);
+    pub const GRAPHIC: Self = AsciiClasses(1 << 5);
+    pub const HEXDIGIT: Self = AsciiClasses(1 << 6);
+    pub const LOWERCASE: Self = AsciiClasses(1 << 7);
+    pub const PUNCTUATION: Self = AsciiClasses(1 << 8);
+    pub const UPPERCASE: Self = AsciiClasses(1 << 9);
+    pub const WHITESPACE: Self = AsciiClasses(1 << 10);
+    pub const ALL: Self = AsciiClasses((1 << 11) - 1);
+
+    /// Returns every class that `x` is in. Each class is computed without branches, so
+    /// this costs about as much as calling every `is_ascii_*_2` method.
+    #[must_use]
+    #[inline]
+    pub fn of(x: u8) -> Self {
+        AsciiClasses(
+            (x.is_ascii_2() as u16)
+                | (x.is_ascii_alphabetic_2() as u16) << 1
+                | (x.is_ascii_alphanumeric_2() as u16) << 2
+                | (x.is_ascii_control_2() as u16) << 3
+                | (x.is_ascii_digit_2() as u16) << 4
+                | (x.is_ascii_graphic_2() as u16) << 5
+                | (x.is_ascii_hexdigit_2() as u16) << 6
+                | (x.is_ascii_lowercase_2() as u16) << 7
+                | (x.is_ascii_punctuation_2() as u16) << 8
+                | (x.is_ascii_uppercase_2() as u16) << 9
+                | (x.is_ascii_whitespace_2() as u16) << 10,
+        )
+    }
+
+    /// Returns every class that `x` is in. `char`s above `'\u{7F}'` are in none.
+    #[must_use]
+    #[inline]
+    pub fn of_char(x: char) -> Self {
+        u8::try_from(x).map_or(Self::NONE, Self::of)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the set with the given bits, ignoring bits that aren't classes.
+    #[must_use]
+    #[inline]
+    pub const fn from_bits_truncate(bits: u16) -> Self {
+        AsciiClasses(bits & Self::ALL.0)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` iff every class in `other` is in `self`.
+    #[must_use]
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` iff any class in `other` is in `self`.
+    #[must_use]
+    #[inline]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for AsciiClasses {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        AsciiClasses(self.0 | other.0)
+    }
+}
+
+impl BitAnd for AsciiClasses {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        AsciiClasses(self.0 & other.0)
+    }
+}
+
+impl Not for AsciiClasses {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        AsciiClasses(!self.0 & Self::ALL.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsciiClasses;
+
+    #[test]
+    fn of_matches_core() {
+        for x in 0..=u8::MAX {
+            let classes = AsciiClasses::of(x);
+            let expected = [
+                (AsciiClasses::ASCII, x.is_ascii()),
+                (AsciiClasses::ALPHABETIC, x.is_ascii_alphabetic()),
+                (AsciiClasses::ALPHANUMERIC, x.is_ascii_alphanumeric()),
+                (AsciiClasses::CONTROL, x.is_ascii_control()),
+                (AsciiClasses::DIGIT, x.is_ascii_digit()),
+                (AsciiClasses::GRAPHIC, x.is_ascii_graphic()),
+                (AsciiClasses::HEXDIGIT, x.is_ascii_hexdigit()),
+                (AsciiClasses::LOWERCASE, x.is_ascii_lowercase()),
+                (AsciiClasses::PUNCTUATION, x.is_ascii_punctuation()),
+                (AsciiClasses::UPPERCASE, x.is_ascii_uppercase()),
+                (AsciiClasses::WHITESPACE, x.is_ascii_whitespace()),
+            ];
+            for (class, is_in) in expected {
+                assert!(classes.contains(class) == is_in, "Failed on {}", x);
+            }
+            assert!(AsciiClasses::ALL.contains(classes), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn of_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            let classes = AsciiClasses::of_char(x);
+            assert!(
+                classes.contains(AsciiClasses::ASCII) == x.is_ascii(),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                classes.contains(AsciiClasses::DIGIT) == x.is_ascii_digit(),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                classes.contains(AsciiClasses::WHITESPACE) == x.is_ascii_whitespace(),
+                "Failed on {}",
+                x
+            );
+            assert!(classes.is_empty() != x.is_ascii(), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let word = AsciiClasses::ALPHANUMERIC | AsciiClasses::PUNCTUATION;
+        assert!(word.intersects(AsciiClasses::of(b'_')));
+        assert!(!word.intersects(AsciiClasses::of(b' ')));
+        assert!((word & AsciiClasses::ALPHANUMERIC) == AsciiClasses::ALPHANUMERIC);
+        assert!((!AsciiClasses::NONE) == AsciiClasses::ALL);
+        assert!(AsciiClasses::from_bits_truncate(u16::MAX) == AsciiClasses::ALL);
+        assert!(AsciiClasses::NONE.is_empty());
+        assert!(AsciiClasses::ALL.contains(AsciiClasses::NONE));
+    }
+}
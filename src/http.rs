@@ -0,0 +1,254 @@
+//! Character classes for HTTP/1.1 field names and field values, from RFC 9110,
+//! section 5, with validators that report the first offending byte.
+
+use crate::class::{ascii_bitmap, in_ascii_bitmap, AsciiClass};
+use crate::swar;
+
+// `tchar = "!" / "#" / "$" / "%" / "&" / "'" / "*" / "+" / "-" / "." / "^" / "_" /
+// "`" / "|" / "~" / DIGIT / ALPHA`
+const TCHAR_BITMAP: u128 = ascii_bitmap(&[
+    (b'!', b'!'),
+    (b'#', b'\''),
+    (b'*', b'+'),
+    (b'-', b'.'),
+    (b'0', b'9'),
+    (b'A', b'Z'),
+    (b'^', b'z'),
+    (b'|', b'|'),
+    (b'~', b'~'),
+]);
+
+pub trait IsHttp2 {
+    /// A byte of a token, such as a field name or a method.
+    fn is_http_tchar_2(&self) -> bool;
+    /// A byte that may appear in a field value: `VCHAR`, `obs-text`, space, or
+    /// horizontal tab. For a `char`, `obs-text` is `'\u{80}'` to `'\u{FF}'`.
+    fn is_http_field_value_byte_2(&self) -> bool;
+}
+
+impl IsHttp2 for u8 {
+    #[inline]
+    fn is_http_tchar_2(&self) -> bool {
+        in_ascii_bitmap(TCHAR_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_http_field_value_byte_2(&self) -> bool {
+        // `b' '` through `b'~'` is space plus `VCHAR`, using the same range trick as
+        // `is_ascii_graphic_2`. Everything from 128 up is `obs-text`.
+        let x = *self;
+        (x.wrapping_sub(b' ') < 95) | (x >= 128) | (x == b'\t')
+    }
+}
+
+impl IsHttp2 for char {
+    #[inline]
+    fn is_http_tchar_2(&self) -> bool {
+        in_ascii_bitmap(TCHAR_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_http_field_value_byte_2(&self) -> bool {
+        let x = *self as u32;
+        (x.wrapping_sub(' ' as u32) < 95) | (x.wrapping_sub(128) < 128) | (x == '\t' as u32)
+    }
+}
+
+/// The `tchar` class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HttpTchar;
+
+/// The bytes allowed in a field value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HttpFieldValueByte;
+
+impl AsciiClass for HttpTchar {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_http_tchar_2()
+    }
+
+    #[inline]
+    fn word_matches(&self, word: u64) -> u64 {
+        // `tchar` is `VCHAR`, found with the range trick of `is_ascii_graphic_2`,
+        // minus the delimiters `"(),/:;<=>?@[\]{}`, which form eight strips.
+        let delimiters = swar::eq(word, b'"')
+            | swar::in_range(word, b'(', 2)
+            | swar::eq(word, b',')
+            | swar::eq(word, b'/')
+            | swar::in_range(word, b':', 7)
+            | swar::in_range(word, b'[', 3)
+            | swar::eq(word, b'{')
+            | swar::eq(word, b'}');
+        swar::in_range(word, b'!', 94) & !delimiters
+    }
+
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        swar::position(
+            bytes,
+            |word| !self.word_matches(word) & swar::HIGH_BITS,
+            |x| !x.is_http_tchar_2(),
+        )
+    }
+}
+
+impl AsciiClass for HttpFieldValueByte {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_http_field_value_byte_2()
+    }
+
+    #[inline]
+    fn word_matches(&self, word: u64) -> u64 {
+        // The same three cases as `is_http_field_value_byte_2`, eight bytes at a time.
+        // Bytes from 128 up already have their high bit set.
+        (swar::in_range(word, b' ', 95) | word | swar::eq(word, b'\t')) & swar::HIGH_BITS
+    }
+
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        swar::position(
+            bytes,
+            |word| !self.word_matches(word) & swar::HIGH_BITS,
+            |x| !x.is_http_field_value_byte_2(),
+        )
+    }
+}
+
+/// Checks that `name` is a nonempty token, as field names and methods must be. On
+/// failure, returns the index of the first byte that is not a `tchar`, or 0 if
+/// `name` is empty.
+pub fn validate_token(name: &[u8]) -> Result<(), usize> {
+    if name.is_empty() {
+        return Err(0);
+    }
+    match HttpTchar.find_nonmember(name) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+/// Checks that every byte of `value` may appear in a field value. On failure,
+/// returns the index of the first byte that may not.
+///
+/// This rejects the bytes used in request smuggling, such as a bare CR or LF that
+/// would end the field early, or a NUL that some parsers treat as the end of the
+/// value. Leading and trailing whitespace is allowed, since it is part of the
+/// field line rather than the value.
+pub fn validate_field_value(value: &[u8]) -> Result<(), usize> {
+    match HttpFieldValueByte.find_nonmember(value) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_tchar(x: u8) -> bool {
+        x.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&x)
+    }
+
+    fn is_field_value_byte(x: u8) -> bool {
+        x == b' ' || x == b'\t' || x.is_ascii_graphic() || x >= 0x80
+    }
+
+    #[test]
+    fn tchar_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_http_tchar_2() == is_tchar(x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn field_value_byte_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                x.is_http_field_value_byte_2() == is_field_value_byte(x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn classes_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            let byte = u8::try_from(x).ok();
+            assert!(
+                x.is_http_tchar_2() == byte.is_some_and(is_tchar),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                x.is_http_field_value_byte_2() == byte.is_some_and(is_field_value_byte),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn every_byte_at_every_offset() {
+        for x in 0..=u8::MAX {
+            for len in 1..=24 {
+                for index in 0..len {
+                    let mut token = [b'a'; 24];
+                    token[index] = x;
+                    let expected = if is_tchar(x) { Ok(()) } else { Err(index) };
+                    assert_eq!(validate_token(&token[..len]), expected, "Failed on {}", x);
+
+                    let mut value = [b' '; 24];
+                    value[index] = x;
+                    let expected = if is_field_value_byte(x) {
+                        Ok(())
+                    } else {
+                        Err(index)
+                    };
+                    assert_eq!(
+                        validate_field_value(&value[..len]),
+                        expected,
+                        "Failed on {}",
+                        x
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(validate_token(b"Content-Type"), Ok(()));
+        assert_eq!(validate_token(b"X-Custom_Header.v2"), Ok(()));
+        assert_eq!(validate_token(b"GET"), Ok(()));
+        assert_eq!(validate_token(b""), Err(0));
+        assert_eq!(validate_token(b"Content-Length:"), Err(14));
+        assert_eq!(validate_token(b"Content Length"), Err(7));
+        assert_eq!(validate_token(b"Transfer-Encoding\t"), Err(17));
+        assert_eq!(validate_token(b"Host\xC3\xA9"), Err(4));
+    }
+
+    #[test]
+    fn smuggling_cases() {
+        assert_eq!(validate_field_value(b"text/html; charset=utf-8"), Ok(()));
+        assert_eq!(validate_field_value(b"\t spaced out \t"), Ok(()));
+        assert_eq!(validate_field_value(b"caf\xC3\xA9"), Ok(()));
+        assert_eq!(validate_field_value(b""), Ok(()));
+
+        // A bare CR or LF, or a full CRLF, could start a new field or request.
+        assert_eq!(validate_field_value(b"chunked\rContent-Length: 0"), Err(7));
+        assert_eq!(validate_field_value(b"chunked\nContent-Length: 0"), Err(7));
+        assert_eq!(
+            validate_field_value(b"a\r\n\r\nGET /admin HTTP/1.1"),
+            Err(1)
+        );
+        // Obsolete line folding is not allowed in values either.
+        assert_eq!(validate_field_value(b"first\r\n second"), Err(5));
+        // NUL truncates the value in C-based parsers.
+        assert_eq!(validate_field_value(b"example.com\0.evil.com"), Err(11));
+        assert_eq!(validate_field_value(b"\x7F"), Err(0));
+        assert_eq!(validate_field_value(b"gzip, chunked\x0B"), Err(13));
+    }
+}
@@ -0,0 +1,348 @@
+//! The ASCII subsets of the identifier grammars of some programming languages, for
+//! lexers.
+//!
+//! | Language   | Start                | Continue                       |
+//! |------------|----------------------|--------------------------------|
+//! | Rust       | letters, `_`         | letters, digits, `_`           |
+//! | C          | letters, `_`         | letters, digits, `_`           |
+//! | JavaScript | letters, `_`, `$`    | letters, digits, `_`, `$`      |
+//! | SQL        | letters              | letters, digits, `_`           |
+//!
+//! SQL is the standard's `<regular identifier>`. Some databases also allow `_` at the
+//! start or `$` after it.
+
+// The letters and digits are handled with `handle_strip_of_each_chunk`, one strip per
+// 32-codepoint chunk. `_` can't get a chunk of its own, since it's 5 codepoints after
+// `Z` and 2 before `a`, and no two chunk boundaries are that close together, so it and
+// `$` are compared separately.
+
+pub trait IsIdent2 {
+    fn is_rust_ident_start_2(&self) -> bool;
+    fn is_rust_ident_continue_2(&self) -> bool;
+    fn is_c_ident_start_2(&self) -> bool;
+    fn is_c_ident_continue_2(&self) -> bool;
+    fn is_js_ident_start_2(&self) -> bool;
+    fn is_js_ident_continue_2(&self) -> bool;
+    fn is_sql_ident_start_2(&self) -> bool;
+    fn is_sql_ident_continue_2(&self) -> bool;
+}
+
+impl IsIdent2 for u8 {
+    #[inline]
+    fn is_rust_ident_start_2(&self) -> bool {
+        let x = *self;
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        ) | (x == b'_')
+    }
+
+    #[inline]
+    fn is_rust_ident_continue_2(&self) -> bool {
+        let x = *self;
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            [0, b'0', b'A', b'a', 0, 0, 0, 0],
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
+        ) | (x == b'_')
+    }
+
+    #[inline]
+    fn is_c_ident_start_2(&self) -> bool {
+        self.is_rust_ident_start_2()
+    }
+
+    #[inline]
+    fn is_c_ident_continue_2(&self) -> bool {
+        self.is_rust_ident_continue_2()
+    }
+
+    #[inline]
+    fn is_js_ident_start_2(&self) -> bool {
+        let x = *self;
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        ) | (x == b'_')
+            | (x == b'$')
+    }
+
+    #[inline]
+    fn is_js_ident_continue_2(&self) -> bool {
+        let x = *self;
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            [0, b'0', b'A', b'a', 0, 0, 0, 0],
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
+        ) | (x == b'_')
+            | (x == b'$')
+    }
+
+    #[inline]
+    fn is_sql_ident_start_2(&self) -> bool {
+        let x = *self;
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        )
+    }
+
+    #[inline]
+    fn is_sql_ident_continue_2(&self) -> bool {
+        self.is_rust_ident_continue_2()
+    }
+}
+
+impl IsIdent2 for char {
+    #[inline]
+    fn is_rust_ident_start_2(&self) -> bool {
+        let x = *self as u32;
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        ) | (x == '_' as u32)
+    }
+
+    #[inline]
+    fn is_rust_ident_continue_2(&self) -> bool {
+        let x = *self as u32;
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            [0, b'0', b'A', b'a', 0, 0, 0, 0],
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
+        ) | (x == '_' as u32)
+    }
+
+    #[inline]
+    fn is_c_ident_start_2(&self) -> bool {
+        self.is_rust_ident_start_2()
+    }
+
+    #[inline]
+    fn is_c_ident_continue_2(&self) -> bool {
+        self.is_rust_ident_continue_2()
+    }
+
+    #[inline]
+    fn is_js_ident_start_2(&self) -> bool {
+        let x = *self as u32;
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        ) | (x == '_' as u32)
+            | (x == '$' as u32)
+    }
+
+    #[inline]
+    fn is_js_ident_continue_2(&self) -> bool {
+        let x = *self as u32;
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            [0, b'0', b'A', b'a', 0, 0, 0, 0],
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
+        ) | (x == '_' as u32)
+            | (x == '$' as u32)
+    }
+
+    #[inline]
+    fn is_sql_ident_start_2(&self) -> bool {
+        let x = *self as u32;
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            [0, 0, b'A', b'a', 0, 0, 0, 0],
+            [0, 0, 26, 26, 0, 0, 0, 0],
+            set: [(b'A', b'Z'), (b'a', b'z')]
+        )
+    }
+
+    #[inline]
+    fn is_sql_ident_continue_2(&self) -> bool {
+        self.is_rust_ident_continue_2()
+    }
+}
+
+crate::class::class_types! {
+    /// The class of `is_rust_ident_start_2`.
+    RustIdentStart => is_rust_ident_start_2,
+    /// The class of `is_rust_ident_continue_2`.
+    RustIdentContinue => is_rust_ident_continue_2,
+    /// The class of `is_c_ident_start_2`.
+    CIdentStart => is_c_ident_start_2,
+    /// The class of `is_c_ident_continue_2`.
+    CIdentContinue => is_c_ident_continue_2,
+    /// The class of `is_js_ident_start_2`.
+    JsIdentStart => is_js_ident_start_2,
+    /// The class of `is_js_ident_continue_2`.
+    JsIdentContinue => is_js_ident_continue_2,
+    /// The class of `is_sql_ident_start_2`.
+    SqlIdentStart => is_sql_ident_start_2,
+    /// The class of `is_sql_ident_continue_2`.
+    SqlIdentContinue => is_sql_ident_continue_2,
+}
+
+// A start byte followed by any number of continue bytes.
+macro_rules! prefix_len_functions {
+    ($($(#[$attr: meta])* $name: ident => $start: ident, $continue: ident;)*) => {
+        $(
+            $(#[$attr])*
+            #[must_use]
+            #[inline]
+            pub fn $name(bytes: &[u8]) -> usize {
+                match bytes.split_first() {
+                    Some((first, rest)) if first.$start() => {
+                        1 + rest.iter().position(|x| !x.$continue()).unwrap_or(rest.len())
+                    }
+                    _ => 0,
+                }
+            }
+        )*
+    };
+}
+
+prefix_len_functions! {
+    /// Returns the length of the longest Rust identifier at the start of `bytes`, or 0
+    /// if there is none.
+    rust_ident_prefix_len => is_rust_ident_start_2, is_rust_ident_continue_2;
+    /// Returns the length of the longest C identifier at the start of `bytes`, or 0 if
+    /// there is none.
+    c_ident_prefix_len => is_c_ident_start_2, is_c_ident_continue_2;
+    /// Returns the length of the longest JavaScript identifier at the start of
+    /// `bytes`, or 0 if there is none.
+    js_ident_prefix_len => is_js_ident_start_2, is_js_ident_continue_2;
+    /// Returns the length of the longest SQL identifier at the start of `bytes`, or 0
+    /// if there is none.
+    sql_ident_prefix_len => is_sql_ident_start_2, is_sql_ident_continue_2;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The grammars in the table at the top of this module, written with `core`'s
+    // predicates.
+    fn letter(x: char) -> bool {
+        x.is_ascii_alphabetic()
+    }
+
+    fn letter_or_digit(x: char) -> bool {
+        x.is_ascii_alphanumeric()
+    }
+
+    fn rust_start(x: char) -> bool {
+        letter(x) || x == '_'
+    }
+
+    fn rust_continue(x: char) -> bool {
+        letter_or_digit(x) || x == '_'
+    }
+
+    fn js_start(x: char) -> bool {
+        rust_start(x) || x == '$'
+    }
+
+    fn js_continue(x: char) -> bool {
+        rust_continue(x) || x == '$'
+    }
+
+    macro_rules! exhaustive {
+        ($($u8_test: ident, $char_test: ident: $method: ident == $expected: ident,)*) => {
+            $(
+                #[test]
+                fn $u8_test() {
+                    for x in 0..=u8::MAX {
+                        assert!(x.$method() == $expected(x as char), "Failed on {}", x);
+                    }
+                }
+
+                #[test]
+                fn $char_test() {
+                    for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+                        assert!(x.$method() == $expected(x), "Failed on {}", x);
+                    }
+                }
+            )*
+        };
+    }
+
+    exhaustive! {
+        rust_start_u8, rust_start_char: is_rust_ident_start_2 == rust_start,
+        rust_continue_u8, rust_continue_char: is_rust_ident_continue_2 == rust_continue,
+        c_start_u8, c_start_char: is_c_ident_start_2 == rust_start,
+        c_continue_u8, c_continue_char: is_c_ident_continue_2 == rust_continue,
+        js_start_u8, js_start_char: is_js_ident_start_2 == js_start,
+        js_continue_u8, js_continue_char: is_js_ident_continue_2 == js_continue,
+        sql_start_u8, sql_start_char: is_sql_ident_start_2 == letter,
+        sql_continue_u8, sql_continue_char: is_sql_ident_continue_2 == rust_continue,
+    }
+
+    #[test]
+    fn prefix_lens() {
+        assert_eq!(rust_ident_prefix_len(b""), 0);
+        assert_eq!(rust_ident_prefix_len(b"foo_bar2 = 1"), 8);
+        assert_eq!(rust_ident_prefix_len(b"_x"), 2);
+        assert_eq!(rust_ident_prefix_len(b"2x"), 0);
+        assert_eq!(rust_ident_prefix_len(b"$x"), 0);
+        assert_eq!(rust_ident_prefix_len(b"caf\xC3\xA9"), 3);
+        assert_eq!(c_ident_prefix_len(b"size_t;"), 6);
+        assert_eq!(js_ident_prefix_len(b"$el.value"), 3);
+        assert_eq!(js_ident_prefix_len(b"a$b$ + 1"), 4);
+        assert_eq!(sql_ident_prefix_len(b"_tmp"), 0);
+        assert_eq!(sql_ident_prefix_len(b"user_id, name"), 7);
+        assert_eq!(sql_ident_prefix_len(b"t1$x"), 2);
+    }
+
+    #[test]
+    fn prefix_lens_every_pair() {
+        for first in 0..=u8::MAX {
+            for second in 0..=u8::MAX {
+                let bytes = [first, second, b' '];
+                let expected = match (first.is_js_ident_start_2(), second.is_js_ident_continue_2())
+                {
+                    (false, _) => 0,
+                    (true, false) => 1,
+                    (true, true) => 2,
+                };
+                assert_eq!(
+                    js_ident_prefix_len(&bytes),
+                    expected,
+                    "Failed on {:?}",
+                    bytes
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn classes() {
+        use crate::AsciiClass;
+
+        assert!(JsIdentStart.contains(b'$'));
+        assert!(!RustIdentStart.contains(b'$'));
+        assert!(SqlIdentContinue.contains_all(b"user_id"));
+        assert_eq!(CIdentContinue.find_nonmember(b"argv[1]"), Some(4));
+    }
+}
@@ -0,0 +1,249 @@
+//! Iterating over the indices of every byte in a class.
+//!
+//! The bytes are tested 64 at a time, with the eight `word_matches` masks of a block
+//! packed into a `u64` that has one bit per byte. The iterator then walks the set
+//! bits with `trailing_zeros`, so runs of bytes not in the class cost almost
+//! nothing.
+
+use core::iter::FusedIterator;
+
+use crate::class::*;
+use crate::swar;
+use crate::AsciiClass;
+
+const BLOCK_LEN: usize = 64;
+
+/// Returns a mask with bit `i` set iff `block[i]` is in `class`.
+#[inline]
+fn block_matches<C: AsciiClass>(class: &C, block: &[u8]) -> u64 {
+    debug_assert!(block.len() <= BLOCK_LEN);
+    let mut words = block.chunks_exact(8);
+    let mut matches = 0;
+    for (i, word) in (&mut words).enumerate() {
+        matches |= swar::pack(class.word_matches(swar::load(word))) << (i * 8);
+    }
+    let offset = block.len() / 8 * 8;
+    for (i, &x) in words.remainder().iter().enumerate() {
+        matches |= (class.contains(x) as u64) << (offset + i);
+    }
+    matches
+}
+
+/// An iterator over the indices of the bytes of a slice that are in a class. See
+/// `AsciiIndices2`.
+#[derive(Clone, Debug)]
+pub struct Indices<'a, C> {
+    class: C,
+    // The blocks after the current one.
+    rest: &'a [u8],
+    // The index of the first byte after the current block. Every block but the last
+    // is full, so the current block starts at `block_end - BLOCK_LEN`.
+    block_end: usize,
+    // The matches of the current block that haven't been returned yet.
+    matches: u64,
+}
+
+impl<'a, C: AsciiClass> Indices<'a, C> {
+    #[inline]
+    fn new(bytes: &'a [u8], class: C) -> Self {
+        Indices {
+            class,
+            rest: bytes,
+            block_end: 0,
+            matches: 0,
+        }
+    }
+}
+
+impl<C: AsciiClass> Iterator for Indices<'_, C> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.matches == 0 {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let (block, rest) = self.rest.split_at(self.rest.len().min(BLOCK_LEN));
+            self.matches = block_matches(&self.class, block);
+            self.rest = rest;
+            self.block_end += BLOCK_LEN;
+        }
+        let i = self.matches.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.matches &= self.matches - 1;
+        Some(self.block_end - BLOCK_LEN + i)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.matches.count_ones() as usize;
+        (pending, Some(pending + self.rest.len()))
+    }
+}
+
+impl<C: AsciiClass> FusedIterator for Indices<'_, C> {}
+
+pub trait AsciiIndices2 {
+    /// Returns an iterator over the indices of the bytes of `self` that are in
+    /// `class`, in increasing order.
+    fn class_indices_2<C: AsciiClass>(&self, class: C) -> Indices<'_, C>;
+
+    #[inline]
+    fn ascii_indices_2(&self) -> Indices<'_, Ascii> {
+        self.class_indices_2(Ascii)
+    }
+
+    #[inline]
+    fn ascii_alphabetic_indices_2(&self) -> Indices<'_, AsciiAlphabetic> {
+        self.class_indices_2(AsciiAlphabetic)
+    }
+
+    #[inline]
+    fn ascii_alphanumeric_indices_2(&self) -> Indices<'_, AsciiAlphanumeric> {
+        self.class_indices_2(AsciiAlphanumeric)
+    }
+
+    #[inline]
+    fn ascii_control_indices_2(&self) -> Indices<'_, AsciiControl> {
+        self.class_indices_2(AsciiControl)
+    }
+
+    #[inline]
+    fn ascii_digit_indices_2(&self) -> Indices<'_, AsciiDigit> {
+        self.class_indices_2(AsciiDigit)
+    }
+
+    #[inline]
+    fn ascii_graphic_indices_2(&self) -> Indices<'_, AsciiGraphic> {
+        self.class_indices_2(AsciiGraphic)
+    }
+
+    #[inline]
+    fn ascii_hexdigit_indices_2(&self) -> Indices<'_, AsciiHexdigit> {
+        self.class_indices_2(AsciiHexdigit)
+    }
+
+    #[inline]
+    fn ascii_lowercase_indices_2(&self) -> Indices<'_, AsciiLowercase> {
+        self.class_indices_2(AsciiLowercase)
+    }
+
+    #[inline]
+    fn ascii_punctuation_indices_2(&self) -> Indices<'_, AsciiPunctuation> {
+        self.class_indices_2(AsciiPunctuation)
+    }
+
+    #[inline]
+    fn ascii_uppercase_indices_2(&self) -> Indices<'_, AsciiUppercase> {
+        self.class_indices_2(AsciiUppercase)
+    }
+
+    #[inline]
+    fn ascii_whitespace_indices_2(&self) -> Indices<'_, AsciiWhitespace> {
+        self.class_indices_2(AsciiWhitespace)
+    }
+}
+
+impl AsciiIndices2 for [u8] {
+    #[inline]
+    fn class_indices_2<C: AsciiClass>(&self, class: C) -> Indices<'_, C> {
+        Indices::new(self, class)
+    }
+}
+
+impl AsciiIndices2 for str {
+    #[inline]
+    fn class_indices_2<C: AsciiClass>(&self, class: C) -> Indices<'_, C> {
+        Indices::new(self.as_bytes(), class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IsAscii2;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    macro_rules! against_filter {
+        ($($test: ident: $indices: ident == $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    let mut rng = StdRng::seed_from_u64(0);
+                    let mut bytes = [0; 200];
+                    for _ in 0..200 {
+                        let len = rng.gen_range(0..=bytes.len());
+                        // Half of the inputs are ASCII, which most classes are dense
+                        // in.
+                        let max = if rng.gen() { 0x7F } else { 0xFF };
+                        bytes.iter_mut().for_each(|x| *x = rng.gen_range(0..=max));
+                        let bytes = &bytes[..len];
+                        let expected = bytes
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, x)| x.$method())
+                            .map(|(i, _)| i);
+                        assert!(bytes.$indices().eq(expected), "Failed on {:?}", bytes);
+                    }
+                }
+            )*
+        };
+    }
+
+    against_filter! {
+        ascii: ascii_indices_2 == is_ascii_2,
+        alphabetic: ascii_alphabetic_indices_2 == is_ascii_alphabetic_2,
+        alphanumeric: ascii_alphanumeric_indices_2 == is_ascii_alphanumeric_2,
+        control: ascii_control_indices_2 == is_ascii_control_2,
+        digit: ascii_digit_indices_2 == is_ascii_digit_2,
+        graphic: ascii_graphic_indices_2 == is_ascii_graphic_2,
+        hexdigit: ascii_hexdigit_indices_2 == is_ascii_hexdigit_2,
+        lowercase: ascii_lowercase_indices_2 == is_ascii_lowercase_2,
+        punctuation: ascii_punctuation_indices_2 == is_ascii_punctuation_2,
+        uppercase: ascii_uppercase_indices_2 == is_ascii_uppercase_2,
+        whitespace: ascii_whitespace_indices_2 == is_ascii_whitespace_2,
+    }
+
+    #[test]
+    fn every_byte_at_every_offset() {
+        for x in 0..=u8::MAX {
+            for index in [0, 1, 7, 8, 63, 64, 65, 127, 128, 129] {
+                let mut bytes = [b'a'; 130];
+                bytes[index] = x;
+                let expected = x.is_ascii_punctuation_2().then_some(index);
+                assert_eq!(
+                    bytes.ascii_punctuation_indices_2().next(),
+                    expected,
+                    "Failed on {}",
+                    x
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn custom_classes() {
+        use crate::http::HttpTchar;
+        use crate::uri::UriGenDelim;
+
+        let uri = "https://example.com/a?b=c#d";
+        assert!(uri.class_indices_2(UriGenDelim).eq([5, 6, 7, 19, 21, 25]));
+        let field = b"Content-Type: text/plain";
+        assert!(field
+            .class_indices_2(HttpTchar)
+            .eq((0..12).chain(14..18).chain(19..24)));
+        assert!(b"".ascii_indices_2().next().is_none());
+    }
+
+    #[test]
+    fn size_hint() {
+        let bytes = [b' '; 100];
+        let mut indices = bytes.ascii_whitespace_indices_2();
+        assert_eq!(indices.size_hint(), (0, Some(100)));
+        indices.next();
+        assert_eq!(indices.size_hint(), (63, Some(99)));
+        assert_eq!(indices.count(), 99);
+    }
+}
@@ -0,0 +1,186 @@
+//! Finding and escaping the bytes that may not appear unescaped in a JSON string,
+//! from RFC 8259, section 7.
+
+use core::fmt;
+
+use crate::swar;
+
+pub trait JsonEscape2 {
+    /// Returns the index of the first byte that must be escaped inside a JSON
+    /// string: `"`, `\`, or a control byte below `0x20`.
+    fn find_json_escape_2(&self) -> Option<usize>;
+}
+
+#[inline]
+fn needs_escape(x: u8) -> bool {
+    // The low half of `is_ascii_control_2`. JSON allows DEL unescaped.
+    (x < b' ') | (x == b'"') | (x == b'\\')
+}
+
+impl JsonEscape2 for [u8] {
+    #[inline]
+    fn find_json_escape_2(&self) -> Option<usize> {
+        swar::position(
+            self,
+            |word| swar::lt(word, b' ') | swar::eq(word, b'"') | swar::eq(word, b'\\'),
+            needs_escape,
+        )
+    }
+}
+
+impl JsonEscape2 for str {
+    #[inline]
+    fn find_json_escape_2(&self) -> Option<usize> {
+        self.as_bytes().find_json_escape_2()
+    }
+}
+
+/// Writes `s` to `out` with the escaping needed for the contents of a JSON string.
+/// The surrounding quotes are not written.
+///
+/// Runs of bytes that need no escaping are found a word at a time and written with
+/// a single `write_str`. The two-character escapes are used where JSON has them, and
+/// the other control bytes are written as `\u00XX`.
+pub fn escape_json_2<W: fmt::Write + ?Sized>(s: &str, out: &mut W) -> fmt::Result {
+    let mut rest = s;
+    while let Some(i) = rest.find_json_escape_2() {
+        // The byte at `i` is ASCII, so both splits are on `char` boundaries.
+        out.write_str(&rest[..i])?;
+        let x = rest.as_bytes()[i];
+        match x {
+            b'"' => out.write_str("\\\"")?,
+            b'\\' => out.write_str("\\\\")?,
+            b'\x08' => out.write_str("\\b")?,
+            b'\x0C' => out.write_str("\\f")?,
+            b'\n' => out.write_str("\\n")?,
+            b'\r' => out.write_str("\\r")?,
+            b'\t' => out.write_str("\\t")?,
+            _ => {
+                const HEXDIGITS: &[u8; 16] = b"0123456789abcdef";
+                out.write_str("\\u00")?;
+                out.write_char(HEXDIGITS[(x >> 4) as usize] as char)?;
+                out.write_char(HEXDIGITS[(x & 0b1111) as usize] as char)?;
+            }
+        }
+        rest = &rest[i + 1..];
+    }
+    out.write_str(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A `fmt::Write` into a fixed buffer, since there is no `alloc`.
+    struct Buffer {
+        bytes: [u8; 512],
+        len: usize,
+        writes: usize,
+    }
+
+    impl Buffer {
+        fn new() -> Self {
+            Buffer {
+                bytes: [0; 512],
+                len: 0,
+                writes: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn find_every_byte_at_every_offset() {
+        for x in 0..=u8::MAX {
+            for len in 1..=24 {
+                for index in 0..len {
+                    let mut bytes = [b'a'; 24];
+                    bytes[index] = x;
+                    let expected = if x < 0x20 || x == b'"' || x == b'\\' {
+                        Some(index)
+                    } else {
+                        None
+                    };
+                    assert_eq!(
+                        bytes[..len].find_json_escape_2(),
+                        expected,
+                        "Failed on {}",
+                        x
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn find_str() {
+        assert_eq!("".find_json_escape_2(), None);
+        assert_eq!(
+            "plain text, \u{7F} and caf\u{e9}".find_json_escape_2(),
+            None
+        );
+        assert_eq!("say \"hi\"".find_json_escape_2(), Some(4));
+        assert_eq!("C:\\path".find_json_escape_2(), Some(2));
+        assert_eq!("line one\nline two".find_json_escape_2(), Some(8));
+    }
+
+    #[test]
+    fn escapes() {
+        let mut buffer = Buffer::new();
+        escape_json_2("\"\\\x08\x0C\n\r\t\0\x1F\x7F/", &mut buffer).unwrap();
+        assert_eq!(
+            buffer.as_str(),
+            "\\\"\\\\\\b\\f\\n\\r\\t\\u0000\\u001f\x7F/"
+        );
+
+        let mut buffer = Buffer::new();
+        escape_json_2("caf\u{e9} \u{1F600}", &mut buffer).unwrap();
+        assert_eq!(buffer.as_str(), "caf\u{e9} \u{1F600}");
+    }
+
+    #[test]
+    fn clean_runs_are_written_in_bulk() {
+        let mut buffer = Buffer::new();
+        escape_json_2("a long run of text without escapes", &mut buffer).unwrap();
+        assert_eq!(buffer.writes, 1);
+
+        let mut buffer = Buffer::new();
+        escape_json_2("key: \"value\" and more", &mut buffer).unwrap();
+        assert_eq!(buffer.as_str(), "key: \\\"value\\\" and more");
+        assert_eq!(buffer.writes, 5);
+    }
+
+    #[test]
+    fn every_control_byte() {
+        for x in 0..0x20_u8 {
+            let mut input = [0; 1];
+            let input = (x as char).encode_utf8(&mut input);
+            let mut buffer = Buffer::new();
+            escape_json_2(input, &mut buffer).unwrap();
+            let escaped = buffer.as_str();
+            assert!(escaped.starts_with('\\'), "Failed on {}", x);
+            assert!(escaped.find_json_escape_2() == Some(0), "Failed on {}", x);
+            assert!(
+                escaped[1..].find_json_escape_2().is_none(),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+}
@@ -1,18 +1,49 @@
 #![no_std]
-#![feature(const_slice_index, const_trait_impl)]
+#![feature(const_slice_index, const_trait_impl, portable_simd)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 // This macro creates part of a function that handles up to eight strips of consecutive
 // matching codepoints. The strips must all be in separate 32-codepoint chunks
 // (codepoints 0 to 31, 32 to 63, 64 to 95, 96 to 127, 128 to 159, 160 to 191,
-// 192 to 223, or 224 to 255).
+// 192 to 223, or 224 to 255). The `strip_search` tool in `tools/` finds offsets that
+// make a set fit, along with cheaper encodings when there are any.
+//
+// A chunk length can be given before the tables for sets that don't fit 32-codepoint
+// chunks. It must be a power of two up to 128, and the tables then have one entry per
+// chunk: 4 entries for 64-codepoint chunks, 16 for 16-codepoint chunks, and so on.
+// Smaller chunks fit more sets, but need bigger tables. `Base64Standard` uses
+// 16-codepoint chunks, since `+` and `/` are too close together for 32.
+//
+// After the tables comes the set they're meant to encode, as inclusive ranges of
+// codepoints, which is checked against the tables at compile time along with every
+// strip lying inside its own chunk. If `$x` isn't the codepoint itself, but the
+// codepoint with `| mask` and then `+ offset` applied, as in
+// `is_ascii_punctuation_2`, the mask and offset come before the set so that the set
+// can be given in terms of the codepoints.
 macro_rules! handle_strip_of_each_chunk {
-    ($x: ident, $x_type: ty, $starting_codepoints: expr, $strip_lengths: expr) => {{
-        // 32-codepoint chunk number.
-        let chunk_number = ($x as u8 >> 5) as usize;
+    (
+        $x: ident,
+        $x_type: ty,
+        $chunk_length: literal,
+        $starting_codepoints: expr,
+        $strip_lengths: expr,
+        $(mask: $mask: expr,)?
+        $(offset: $offset: expr,)?
+        set: $set: expr $(,)?
+    ) => {{
+        const CHUNK_LENGTH: usize = $chunk_length;
+        const CHUNK_COUNT: usize = 256 / CHUNK_LENGTH;
+
+        // Chunk number.
+        let chunk_number = ($x as u8 >> CHUNK_LENGTH.trailing_zeros()) as usize;
 
         // `const` to type check and to ensure all element evaluations are done at
         // compile time
-        const STARTING_CODEPOINTS: [u8; 8] = $starting_codepoints;
+        const STARTING_CODEPOINTS: [u8; CHUNK_COUNT] = $starting_codepoints;
         // Subtract the starting codepoint of this chunk from the input codepoint. This
         // will make sure that the matching codepoints in this strip are in
         // `0..length_of_strip`.
@@ -20,13 +51,202 @@ macro_rules! handle_strip_of_each_chunk {
 
         // `const` to type check and to ensure all element evaluations are done at
         // compile time
-        const STRIP_LENGTHS: [u8; 8] = $strip_lengths;
+        const STRIP_LENGTHS: [u8; CHUNK_COUNT] = $strip_lengths;
+        const _: () = $crate::assert_strip_tables(
+            CHUNK_LENGTH,
+            &STARTING_CODEPOINTS,
+            &STRIP_LENGTHS,
+            0 $(| $mask)?,
+            0 $(+ $offset)?,
+            &$set,
+        );
         // Check whether the adjusted value of the input codepoint is in
         // `0..length_of_strip`.
         x < STRIP_LENGTHS[chunk_number] as $x_type
     }};
+    (
+        $x: ident,
+        $x_type: ty,
+        $starting_codepoints: expr,
+        $strip_lengths: expr,
+        $($rest: tt)*
+    ) => {
+        handle_strip_of_each_chunk!(
+            $x,
+            $x_type,
+            32,
+            $starting_codepoints,
+            $strip_lengths,
+            $($rest)*
+        )
+    };
+}
+
+/// The compile-time checks of `handle_strip_of_each_chunk`. `set` holds inclusive
+/// ranges of the codepoints that should match, before `mask` and `offset` are applied.
+///
+/// This is only public so that these examples can check that bad tables don't
+/// compile. The tables for `is_ascii_punctuation_2` pass:
+///
+/// ```
+/// const _: () = is_ascii_speedups::assert_strip_tables(
+///     32,
+///     &[0, b'!' + 6, b':' + 6, b'[' + 6, b'{' + 6, 0, 0, 0],
+///     &[0, 15, 7, 6, 4, 0, 0, 0],
+///     0,
+///     6,
+///     &[(b'!', b'/'), (b':', b'@'), (b'[', b'`'), (b'{', b'~')],
+/// );
+/// ```
+///
+/// A strip that crosses into the next chunk doesn't, since the codepoints past the
+/// chunk would be looked up in the next chunk's strip:
+///
+/// ```compile_fail,E0080
+/// const _: () = is_ascii_speedups::assert_strip_tables(
+///     32,
+///     &[0, b'0', 0, 0, 0, 0, 0, 0],
+///     &[0, 20, 0, 0, 0, 0, 0, 0],
+///     0,
+///     0,
+///     &[(b'0', b'C')],
+/// );
+/// ```
+///
+/// Nor do tables that encode a different set than `set`, here without `'9'`:
+///
+/// ```compile_fail,E0080
+/// const _: () = is_ascii_speedups::assert_strip_tables(
+///     32,
+///     &[0, b'0', 0, 0, 0, 0, 0, 0],
+///     &[0, 9, 0, 0, 0, 0, 0, 0],
+///     0,
+///     0,
+///     &[(b'0', b'9')],
+/// );
+/// ```
+///
+/// Nor does an offset that carries a member of the set past 255, even if the tables
+/// match after it wraps around:
+///
+/// ```compile_fail,E0080
+/// const _: () = is_ascii_speedups::assert_strip_tables(
+///     32,
+///     &[0, 0, 0, 0, 0, 0, 0, 0],
+///     &[1, 0, 0, 0, 0, 0, 0, 0],
+///     0,
+///     1,
+///     &[(255, 255)],
+/// );
+/// ```
+#[doc(hidden)]
+pub const fn assert_strip_tables(
+    chunk_length: usize,
+    starts: &[u8],
+    lengths: &[u8],
+    mask: u8,
+    offset: u8,
+    set: &[(u8, u8)],
+) {
+    // Past 128, a `char` from 256 up could land in a strip after the subtraction,
+    // since its chunk number only comes from its low byte.
+    assert!(
+        chunk_length.is_power_of_two() && chunk_length <= 128,
+        "chunk lengths must be powers of two up to 128"
+    );
+    let mut chunk_number = 0;
+    while chunk_number < starts.len() {
+        let chunk_start = chunk_number * chunk_length;
+        let start = starts[chunk_number] as usize;
+        let length = lengths[chunk_number] as usize;
+        assert!(
+            length == 0 || (start >= chunk_start && start + length <= chunk_start + chunk_length),
+            "every strip must lie inside its own chunk"
+        );
+        chunk_number += 1;
+    }
+
+    let mut codepoint = 0;
+    while codepoint < 256 {
+        let mut in_set = false;
+        let mut i = 0;
+        while i < set.len() {
+            let (first, last) = set[i];
+            in_set |= first as usize <= codepoint && codepoint <= last as usize;
+            i += 1;
+        }
+        let x = (codepoint as u8 | mask) as usize + offset as usize;
+        assert!(
+            !in_set || x < 256,
+            "the offset must not carry a codepoint of the set past 255, since a `char` \
+             doesn't wrap around there"
+        );
+        let x = x % 256;
+        let chunk_number = x / chunk_length;
+        let in_strip =
+            x.wrapping_sub(starts[chunk_number] as usize) < lengths[chunk_number] as usize;
+        assert!(in_set == in_strip, "the strip tables don't match the set");
+        codepoint += 1;
+    }
+}
+
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod bracket;
+pub mod class;
+pub mod codec;
+mod cpu;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+pub mod flags;
+pub mod http;
+pub mod ident;
+pub mod indices;
+pub mod json;
+pub mod posix;
+pub mod replace;
+pub mod runs;
+pub mod shuffle;
+pub mod simd;
+pub mod split;
+mod swar;
+pub mod table;
+pub mod trim;
+pub mod tune;
+pub mod uri;
+pub mod whitespace;
+
+pub use class::AsciiClass;
+#[cfg(feature = "macros")]
+pub use is_ascii_speedups_macros::ascii_class;
+
+/// How `IsAscii2` classifies in this build, chosen with the `strategy-*` features.
+///
+/// `strategy-branchless` is the default. If more than one is enabled, `strategy-core`
+/// wins over `strategy-table`, which wins over `strategy-branchless`, so a build can
+/// pick another strategy without turning off the default features.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// The branchless range, bitmap and strip tests in this file.
+    Branchless,
+    /// Lookups in `table::CLASSES`, for cores where a load beats the arithmetic.
+    Table,
+    /// `core`'s `is_ascii_*` methods.
+    Core,
 }
 
+/// The strategy compiled into this build.
+pub const STRATEGY: Strategy = if cfg!(feature = "strategy-core") {
+    Strategy::Core
+} else if cfg!(feature = "strategy-table") {
+    Strategy::Table
+} else {
+    Strategy::Branchless
+};
+
+/// The `is_ascii_*` methods of `core`, implemented with the strategy in `STRATEGY`.
 pub trait IsAscii2 {
     fn is_ascii_2(&self) -> bool;
     fn is_ascii_alphabetic_2(&self) -> bool;
@@ -41,6 +261,7 @@ pub trait IsAscii2 {
     fn is_ascii_whitespace_2(&self) -> bool;
 }
 
+#[cfg(not(any(feature = "strategy-core", feature = "strategy-table")))]
 impl IsAscii2 for u8 {
     #[must_use]
     #[inline]
@@ -67,7 +288,8 @@ impl IsAscii2 for u8 {
             x,
             u8,
             [0, b'0', b'A', b'a', 0, 0, 0, 0],
-            [0, 10, 26, 26, 0, 0, 0, 0]
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
         )
     }
 
@@ -100,7 +322,8 @@ impl IsAscii2 for u8 {
             x,
             u8,
             [0, b'0', b'A', b'a', 0, 0, 0, 0],
-            [0, 10, 6, 6, 0, 0, 0, 0]
+            [0, 10, 6, 6, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'F'), (b'a', b'f')]
         )
     }
 
@@ -122,7 +345,9 @@ impl IsAscii2 for u8 {
             x,
             u8,
             [0, b'!' + 6, b':' + 6, b'[' + 6, b'{' + 6, 0, 0, 0],
-            [0, 15, 7, 6, 4, 0, 0, 0]
+            [0, 15, 7, 6, 4, 0, 0, 0],
+            offset: 6,
+            set: [(b'!', b'/'), (b':', b'@'), (b'[', b'`'), (b'{', b'~')]
         )
     }
 
@@ -145,6 +370,7 @@ impl IsAscii2 for u8 {
     }
 }
 
+#[cfg(not(any(feature = "strategy-core", feature = "strategy-table")))]
 impl IsAscii2 for char {
     #[must_use]
     #[inline]
@@ -171,7 +397,8 @@ impl IsAscii2 for char {
             x,
             u32,
             [0, b'0', b'A', b'a', 0, 0, 0, 0],
-            [0, 10, 26, 26, 0, 0, 0, 0]
+            [0, 10, 26, 26, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'Z'), (b'a', b'z')]
         )
     }
 
@@ -204,7 +431,8 @@ impl IsAscii2 for char {
             x,
             u32,
             [0, b'0', b'A', b'a', 0, 0, 0, 0],
-            [0, 10, 6, 6, 0, 0, 0, 0]
+            [0, 10, 6, 6, 0, 0, 0, 0],
+            set: [(b'0', b'9'), (b'A', b'F'), (b'a', b'f')]
         )
     }
 
@@ -226,7 +454,9 @@ impl IsAscii2 for char {
             x,
             u32,
             [0, b'!' + 6, b':' + 6, b'[' + 6, b'{' + 6, 0, 0, 0],
-            [0, 15, 7, 6, 4, 0, 0, 0]
+            [0, 15, 7, 6, 4, 0, 0, 0],
+            offset: 6,
+            set: [(b'!', b'/'), (b':', b'@'), (b'[', b'`'), (b'{', b'~')]
         )
     }
 
@@ -249,14 +479,136 @@ impl IsAscii2 for char {
     }
 }
 
+// Implements `IsAscii2` for `$type` by calling the other strategy's method for
+// each class.
+#[cfg(any(feature = "strategy-core", feature = "strategy-table"))]
+macro_rules! forward_is_ascii_2 {
+    ($type: ty, $($method: ident => $strategy_method: ident,)*) => {
+        impl IsAscii2 for $type {
+            $(
+                #[must_use]
+                #[inline]
+                fn $method(&self) -> bool {
+                    self.$strategy_method()
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(feature = "strategy-core")]
+macro_rules! forward_to_core {
+    ($type: ty) => {
+        forward_is_ascii_2! {
+            $type,
+            is_ascii_2 => is_ascii,
+            is_ascii_alphabetic_2 => is_ascii_alphabetic,
+            is_ascii_alphanumeric_2 => is_ascii_alphanumeric,
+            is_ascii_control_2 => is_ascii_control,
+            is_ascii_digit_2 => is_ascii_digit,
+            is_ascii_graphic_2 => is_ascii_graphic,
+            is_ascii_hexdigit_2 => is_ascii_hexdigit,
+            is_ascii_lowercase_2 => is_ascii_lowercase,
+            is_ascii_punctuation_2 => is_ascii_punctuation,
+            is_ascii_uppercase_2 => is_ascii_uppercase,
+            is_ascii_whitespace_2 => is_ascii_whitespace,
+        }
+    };
+}
+
+#[cfg(feature = "strategy-core")]
+forward_to_core!(u8);
+#[cfg(feature = "strategy-core")]
+forward_to_core!(char);
+
+#[cfg(all(feature = "strategy-table", not(feature = "strategy-core")))]
+macro_rules! forward_to_table {
+    ($type: ty) => {
+        forward_is_ascii_2! {
+            $type,
+            is_ascii_2 => is_ascii_table,
+            is_ascii_alphabetic_2 => is_ascii_alphabetic_table,
+            is_ascii_alphanumeric_2 => is_ascii_alphanumeric_table,
+            is_ascii_control_2 => is_ascii_control_table,
+            is_ascii_digit_2 => is_ascii_digit_table,
+            is_ascii_graphic_2 => is_ascii_graphic_table,
+            is_ascii_hexdigit_2 => is_ascii_hexdigit_table,
+            is_ascii_lowercase_2 => is_ascii_lowercase_table,
+            is_ascii_punctuation_2 => is_ascii_punctuation_table,
+            is_ascii_uppercase_2 => is_ascii_uppercase_table,
+            is_ascii_whitespace_2 => is_ascii_whitespace_table,
+        }
+    };
+}
+
+#[cfg(all(feature = "strategy-table", not(feature = "strategy-core")))]
+use table::IsAsciiTable;
+#[cfg(all(feature = "strategy-table", not(feature = "strategy-core")))]
+forward_to_table!(u8);
+#[cfg(all(feature = "strategy-table", not(feature = "strategy-core")))]
+forward_to_table!(char);
+
 #[cfg(test)]
 mod tests {
+    // These go through `IsAscii2`, so they test whichever strategy is compiled in.
+    // `test_strategies` runs them once per strategy. `--all-features` only tests
+    // `strategy-core`, since it wins over the others.
     use super::IsAscii2;
 
+    // The classes written out from the documentation of `u8`'s methods, so that no
+    // strategy is checked against itself. A `char` is in a class iff it converts to a
+    // byte in it.
+    #[allow(clippy::manual_is_ascii_check)] // `core`'s methods are what's avoided.
+    mod reference {
+        pub fn ascii(x: u8) -> bool {
+            x <= 0x7F
+        }
+
+        pub fn alphabetic(x: u8) -> bool {
+            matches!(x, b'A'..=b'Z' | b'a'..=b'z')
+        }
+
+        pub fn alphanumeric(x: u8) -> bool {
+            matches!(x, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z')
+        }
+
+        pub fn control(x: u8) -> bool {
+            matches!(x, 0x00..=0x1F | 0x7F)
+        }
+
+        pub fn digit(x: u8) -> bool {
+            matches!(x, b'0'..=b'9')
+        }
+
+        pub fn graphic(x: u8) -> bool {
+            matches!(x, 0x21..=0x7E)
+        }
+
+        pub fn hexdigit(x: u8) -> bool {
+            matches!(x, b'0'..=b'9' | b'A'..=b'F' | b'a'..=b'f')
+        }
+
+        pub fn lowercase(x: u8) -> bool {
+            matches!(x, b'a'..=b'z')
+        }
+
+        pub fn punctuation(x: u8) -> bool {
+            matches!(x, 0x21..=0x2F | 0x3A..=0x40 | 0x5B..=0x60 | 0x7B..=0x7E)
+        }
+
+        pub fn uppercase(x: u8) -> bool {
+            matches!(x, b'A'..=b'Z')
+        }
+
+        pub fn whitespace(x: u8) -> bool {
+            matches!(x, b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+        }
+    }
+
     #[test]
     fn ascii_u8() {
         for x in 0..=u8::MAX {
-            assert!(x.is_ascii() == x.is_ascii_2(), "Failed on {}", x);
+            assert!(reference::ascii(x) == x.is_ascii_2(), "Failed on {}", x);
         }
     }
 
@@ -264,7 +616,7 @@ mod tests {
     fn alphabetic_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_alphabetic() == x.is_ascii_alphabetic_2(),
+                reference::alphabetic(x) == x.is_ascii_alphabetic_2(),
                 "Failed on {}",
                 x
             );
@@ -275,7 +627,7 @@ mod tests {
     fn alphanumeric_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_alphanumeric() == x.is_ascii_alphanumeric_2(),
+                reference::alphanumeric(x) == x.is_ascii_alphanumeric_2(),
                 "Failed on {}",
                 x
             );
@@ -286,7 +638,7 @@ mod tests {
     fn control_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_control() == x.is_ascii_control_2(),
+                reference::control(x) == x.is_ascii_control_2(),
                 "Failed on {}",
                 x
             );
@@ -297,7 +649,7 @@ mod tests {
     fn digit_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_digit() == x.is_ascii_digit_2(),
+                reference::digit(x) == x.is_ascii_digit_2(),
                 "Failed on {}",
                 x
             );
@@ -308,7 +660,7 @@ mod tests {
     fn graphic_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_graphic() == x.is_ascii_graphic_2(),
+                reference::graphic(x) == x.is_ascii_graphic_2(),
                 "Failed on {}",
                 x
             );
@@ -319,7 +671,7 @@ mod tests {
     fn hexdigit_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_hexdigit() == x.is_ascii_hexdigit_2(),
+                reference::hexdigit(x) == x.is_ascii_hexdigit_2(),
                 "Failed on {}",
                 x
             );
@@ -330,7 +682,7 @@ mod tests {
     fn lowercase_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_lowercase() == x.is_ascii_lowercase_2(),
+                reference::lowercase(x) == x.is_ascii_lowercase_2(),
                 "Failed on {}",
                 x
             );
@@ -341,7 +693,7 @@ mod tests {
     fn punctuation_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_punctuation() == x.is_ascii_punctuation_2(),
+                reference::punctuation(x) == x.is_ascii_punctuation_2(),
                 "Failed on {}",
                 x
             );
@@ -352,7 +704,7 @@ mod tests {
     fn uppercase_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_uppercase() == x.is_ascii_uppercase_2(),
+                reference::uppercase(x) == x.is_ascii_uppercase_2(),
                 "Failed on {}",
                 x
             );
@@ -363,7 +715,7 @@ mod tests {
     fn whitespace_u8() {
         for x in 0..=u8::MAX {
             assert!(
-                x.is_ascii_whitespace() == x.is_ascii_whitespace_2(),
+                reference::whitespace(x) == x.is_ascii_whitespace_2(),
                 "Failed on {}",
                 x
             );
@@ -373,7 +725,11 @@ mod tests {
     #[test]
     fn ascii_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
-            assert!(x.is_ascii() == x.is_ascii_2(), "Failed on {}", x);
+            assert!(
+                u8::try_from(x).is_ok_and(reference::ascii) == x.is_ascii_2(),
+                "Failed on {}",
+                x
+            );
         }
     }
 
@@ -381,7 +737,7 @@ mod tests {
     fn alphabetic_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_alphabetic() == x.is_ascii_alphabetic_2(),
+                u8::try_from(x).is_ok_and(reference::alphabetic) == x.is_ascii_alphabetic_2(),
                 "Failed on {}",
                 x
             );
@@ -392,7 +748,7 @@ mod tests {
     fn alphanumeric_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_alphanumeric() == x.is_ascii_alphanumeric_2(),
+                u8::try_from(x).is_ok_and(reference::alphanumeric) == x.is_ascii_alphanumeric_2(),
                 "Failed on {}",
                 x
             );
@@ -403,7 +759,7 @@ mod tests {
     fn control_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_control() == x.is_ascii_control_2(),
+                u8::try_from(x).is_ok_and(reference::control) == x.is_ascii_control_2(),
                 "Failed on {}",
                 x
             );
@@ -414,7 +770,7 @@ mod tests {
     fn digit_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_digit() == x.is_ascii_digit_2(),
+                u8::try_from(x).is_ok_and(reference::digit) == x.is_ascii_digit_2(),
                 "Failed on {}",
                 x
             );
@@ -425,7 +781,7 @@ mod tests {
     fn graphic_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_graphic() == x.is_ascii_graphic_2(),
+                u8::try_from(x).is_ok_and(reference::graphic) == x.is_ascii_graphic_2(),
                 "Failed on {}",
                 x
             );
@@ -436,7 +792,7 @@ mod tests {
     fn hexdigit_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_hexdigit() == x.is_ascii_hexdigit_2(),
+                u8::try_from(x).is_ok_and(reference::hexdigit) == x.is_ascii_hexdigit_2(),
                 "Failed on {}",
                 x
             );
@@ -447,7 +803,7 @@ mod tests {
     fn lowercase_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_lowercase() == x.is_ascii_lowercase_2(),
+                u8::try_from(x).is_ok_and(reference::lowercase) == x.is_ascii_lowercase_2(),
                 "Failed on {}",
                 x
             );
@@ -458,7 +814,7 @@ mod tests {
     fn punctuation_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_punctuation() == x.is_ascii_punctuation_2(),
+                u8::try_from(x).is_ok_and(reference::punctuation) == x.is_ascii_punctuation_2(),
                 "Failed on {}",
                 x
             );
@@ -469,7 +825,7 @@ mod tests {
     fn uppercase_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_uppercase() == x.is_ascii_uppercase_2(),
+                u8::try_from(x).is_ok_and(reference::uppercase) == x.is_ascii_uppercase_2(),
                 "Failed on {}",
                 x
             );
@@ -480,7 +836,45 @@ mod tests {
     fn whitespace_char() {
         for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
             assert!(
-                x.is_ascii_whitespace() == x.is_ascii_whitespace_2(),
+                u8::try_from(x).is_ok_and(reference::whitespace) == x.is_ascii_whitespace_2(),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    // ASCII control codepoints in 64-codepoint chunks, for `u8`s and for `char`s as
+    // `u32`s. `Base64Standard` covers 16-codepoint chunks.
+    fn is_control_u8(x: u8) -> bool {
+        handle_strip_of_each_chunk!(
+            x,
+            u8,
+            64,
+            [0, 127, 0, 0],
+            [32, 1, 0, 0],
+            set: [(0, 31), (127, 127)]
+        )
+    }
+
+    fn is_control_u32(x: u32) -> bool {
+        handle_strip_of_each_chunk!(
+            x,
+            u32,
+            64,
+            [0, 127, 0, 0],
+            [32, 1, 0, 0],
+            set: [(0, 31), (127, 127)]
+        )
+    }
+
+    #[test]
+    fn other_chunk_lengths() {
+        for x in 0..=u8::MAX {
+            assert!(reference::control(x) == is_control_u8(x), "Failed on {}", x);
+        }
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                u8::try_from(x).is_ok_and(reference::control) == is_control_u32(x as u32),
                 "Failed on {}",
                 x
             );
@@ -0,0 +1,259 @@
+//! The POSIX character classes with the semantics of C's `<ctype.h>` in the `"C"`
+//! locale, for porting C code.
+//!
+//! The `i32` versions take the same arguments as the C functions: a byte value as
+//! an `unsigned char` converted to `int`, or `EOF`. Every class is false for
+//! `EOF`, and in the `"C"` locale every class is false for bytes from 128 up.
+//! Other negative values are undefined behavior in C, and are in no class here.
+
+use crate::whitespace::IsWhitespace2;
+use crate::IsAscii2;
+
+/// The value C uses for `EOF`.
+pub const EOF: i32 = -1;
+
+pub trait IsPosix2 {
+    fn is_posix_alnum_2(&self) -> bool;
+    fn is_posix_alpha_2(&self) -> bool;
+    fn is_posix_blank_2(&self) -> bool;
+    fn is_posix_cntrl_2(&self) -> bool;
+    fn is_posix_digit_2(&self) -> bool;
+    fn is_posix_graph_2(&self) -> bool;
+    fn is_posix_lower_2(&self) -> bool;
+    fn is_posix_print_2(&self) -> bool;
+    fn is_posix_punct_2(&self) -> bool;
+    fn is_posix_space_2(&self) -> bool;
+    fn is_posix_upper_2(&self) -> bool;
+    fn is_posix_xdigit_2(&self) -> bool;
+}
+
+impl IsPosix2 for u8 {
+    #[inline]
+    fn is_posix_alnum_2(&self) -> bool {
+        self.is_ascii_alphanumeric_2()
+    }
+
+    #[inline]
+    fn is_posix_alpha_2(&self) -> bool {
+        self.is_ascii_alphabetic_2()
+    }
+
+    #[inline]
+    fn is_posix_blank_2(&self) -> bool {
+        self.is_blank_2()
+    }
+
+    #[inline]
+    fn is_posix_cntrl_2(&self) -> bool {
+        self.is_ascii_control_2()
+    }
+
+    #[inline]
+    fn is_posix_digit_2(&self) -> bool {
+        self.is_ascii_digit_2()
+    }
+
+    #[inline]
+    fn is_posix_graph_2(&self) -> bool {
+        self.is_ascii_graphic_2()
+    }
+
+    #[inline]
+    fn is_posix_lower_2(&self) -> bool {
+        self.is_ascii_lowercase_2()
+    }
+
+    #[inline]
+    fn is_posix_print_2(&self) -> bool {
+        // `graph` plus space, so the range starts one codepoint earlier.
+        let x = self.wrapping_sub(b' ');
+        x < 95
+    }
+
+    #[inline]
+    fn is_posix_punct_2(&self) -> bool {
+        self.is_ascii_punctuation_2()
+    }
+
+    #[inline]
+    fn is_posix_space_2(&self) -> bool {
+        // Unlike `is_ascii_whitespace_2`, this includes vertical tab.
+        self.is_c_space_2()
+    }
+
+    #[inline]
+    fn is_posix_upper_2(&self) -> bool {
+        self.is_ascii_uppercase_2()
+    }
+
+    #[inline]
+    fn is_posix_xdigit_2(&self) -> bool {
+        self.is_ascii_hexdigit_2()
+    }
+}
+
+// `EOF` and every other value outside of `0..=255` fail the conversion, and so are
+// in no class.
+macro_rules! via_u8 {
+    ($c: expr, $method: ident) => {
+        u8::try_from($c).is_ok_and(|x| x.$method())
+    };
+}
+
+impl IsPosix2 for i32 {
+    #[inline]
+    fn is_posix_alnum_2(&self) -> bool {
+        via_u8!(*self, is_posix_alnum_2)
+    }
+
+    #[inline]
+    fn is_posix_alpha_2(&self) -> bool {
+        via_u8!(*self, is_posix_alpha_2)
+    }
+
+    #[inline]
+    fn is_posix_blank_2(&self) -> bool {
+        via_u8!(*self, is_posix_blank_2)
+    }
+
+    #[inline]
+    fn is_posix_cntrl_2(&self) -> bool {
+        via_u8!(*self, is_posix_cntrl_2)
+    }
+
+    #[inline]
+    fn is_posix_digit_2(&self) -> bool {
+        via_u8!(*self, is_posix_digit_2)
+    }
+
+    #[inline]
+    fn is_posix_graph_2(&self) -> bool {
+        via_u8!(*self, is_posix_graph_2)
+    }
+
+    #[inline]
+    fn is_posix_lower_2(&self) -> bool {
+        via_u8!(*self, is_posix_lower_2)
+    }
+
+    #[inline]
+    fn is_posix_print_2(&self) -> bool {
+        via_u8!(*self, is_posix_print_2)
+    }
+
+    #[inline]
+    fn is_posix_punct_2(&self) -> bool {
+        via_u8!(*self, is_posix_punct_2)
+    }
+
+    #[inline]
+    fn is_posix_space_2(&self) -> bool {
+        via_u8!(*self, is_posix_space_2)
+    }
+
+    #[inline]
+    fn is_posix_upper_2(&self) -> bool {
+        via_u8!(*self, is_posix_upper_2)
+    }
+
+    #[inline]
+    fn is_posix_xdigit_2(&self) -> bool {
+        via_u8!(*self, is_posix_xdigit_2)
+    }
+}
+
+// Free functions with the C names, so that ported code can keep its calls as they
+// are.
+macro_rules! ctype_functions {
+    ($($name: ident => $method: ident,)*) => {
+        $(
+            #[doc = concat!("C's `", stringify!($name), "` in the `\"C\"` locale.")]
+            #[must_use]
+            #[inline]
+            pub fn $name(c: i32) -> bool {
+                c.$method()
+            }
+        )*
+    };
+}
+
+ctype_functions! {
+    isalnum => is_posix_alnum_2,
+    isalpha => is_posix_alpha_2,
+    isblank => is_posix_blank_2,
+    iscntrl => is_posix_cntrl_2,
+    isdigit => is_posix_digit_2,
+    isgraph => is_posix_graph_2,
+    islower => is_posix_lower_2,
+    isprint => is_posix_print_2,
+    ispunct => is_posix_punct_2,
+    isspace => is_posix_space_2,
+    isupper => is_posix_upper_2,
+    isxdigit => is_posix_xdigit_2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsPosix2;
+
+    mod libc {
+        use core::ffi::c_int;
+
+        extern "C" {
+            pub fn isalnum(c: c_int) -> c_int;
+            pub fn isalpha(c: c_int) -> c_int;
+            pub fn isblank(c: c_int) -> c_int;
+            pub fn iscntrl(c: c_int) -> c_int;
+            pub fn isdigit(c: c_int) -> c_int;
+            pub fn isgraph(c: c_int) -> c_int;
+            pub fn islower(c: c_int) -> c_int;
+            pub fn isprint(c: c_int) -> c_int;
+            pub fn ispunct(c: c_int) -> c_int;
+            pub fn isspace(c: c_int) -> c_int;
+            pub fn isupper(c: c_int) -> c_int;
+            pub fn isxdigit(c: c_int) -> c_int;
+        }
+    }
+
+    // The test binary never calls `setlocale`, so libc is in the `"C"` locale.
+    macro_rules! against_libc {
+        ($($test: ident: $name: ident => $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    for c in -1..=255 {
+                        let expected = unsafe { libc::$name(c) } != 0;
+                        assert!(c.$method() == expected, "Failed on {}", c);
+                        assert!(super::$name(c) == expected, "Failed on {}", c);
+                        if let Ok(x) = u8::try_from(c) {
+                            assert!(x.$method() == expected, "Failed on {}", c);
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    against_libc! {
+        alnum_libc: isalnum => is_posix_alnum_2,
+        alpha_libc: isalpha => is_posix_alpha_2,
+        blank_libc: isblank => is_posix_blank_2,
+        cntrl_libc: iscntrl => is_posix_cntrl_2,
+        digit_libc: isdigit => is_posix_digit_2,
+        graph_libc: isgraph => is_posix_graph_2,
+        lower_libc: islower => is_posix_lower_2,
+        print_libc: isprint => is_posix_print_2,
+        punct_libc: ispunct => is_posix_punct_2,
+        space_libc: isspace => is_posix_space_2,
+        upper_libc: isupper => is_posix_upper_2,
+        xdigit_libc: isxdigit => is_posix_xdigit_2,
+    }
+
+    #[test]
+    fn out_of_range() {
+        for c in [i32::MIN, -129, -2, 256, i32::MAX] {
+            assert!(!c.is_posix_print_2(), "Failed on {}", c);
+            assert!(!c.is_posix_cntrl_2(), "Failed on {}", c);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Replacing the bytes of a class in place, for sanitizing input.
+
+use crate::class::*;
+use crate::swar;
+use crate::trim::{find_non_whitespace, find_whitespace};
+use crate::AsciiClass;
+
+/// Replaces the bytes of `word` whose high bit is set in `matches` with `substitute`.
+#[inline]
+fn blend(word: u64, matches: u64, substitute: u8) -> u64 {
+    // Widen each high bit to its whole byte: `0x80` becomes `0x01`, and then `0xFF`.
+    let mask = (matches >> 7) * 0xFF;
+    (word & !mask) | (swar::splat(substitute) & mask)
+}
+
+pub trait ReplaceAscii2 {
+    /// Replaces every byte of `self` that's in `class` with `substitute`.
+    fn replace_class_2<C: AsciiClass>(&mut self, class: C, substitute: u8);
+
+    /// Replaces every run of ASCII whitespace with a single space, moving the rest of
+    /// the bytes down to fill the gaps. Returns the new length; the bytes after it are
+    /// unspecified.
+    fn collapse_ascii_whitespace_2(&mut self) -> usize;
+
+    #[inline]
+    fn replace_ascii_2(&mut self, substitute: u8) {
+        self.replace_class_2(Ascii, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_alphabetic_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiAlphabetic, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_alphanumeric_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiAlphanumeric, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_control_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiControl, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_digit_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiDigit, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_graphic_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiGraphic, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_hexdigit_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiHexdigit, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_lowercase_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiLowercase, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_punctuation_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiPunctuation, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_uppercase_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiUppercase, substitute)
+    }
+
+    #[inline]
+    fn replace_ascii_whitespace_2(&mut self, substitute: u8) {
+        self.replace_class_2(AsciiWhitespace, substitute)
+    }
+}
+
+impl ReplaceAscii2 for [u8] {
+    #[inline]
+    fn replace_class_2<C: AsciiClass>(&mut self, class: C, substitute: u8) {
+        let mut words = self.chunks_exact_mut(8);
+        for word in &mut words {
+            let x = swar::load(word);
+            word.copy_from_slice(&blend(x, class.word_matches(x), substitute).to_le_bytes());
+        }
+        for x in words.into_remainder() {
+            if class.contains(*x) {
+                *x = substitute;
+            }
+        }
+    }
+
+    fn collapse_ascii_whitespace_2(&mut self) -> usize {
+        let mut read = 0;
+        let mut write = 0;
+        while read < self.len() {
+            // Copy the run of other bytes, then one space for the run of whitespace
+            // after it, if there is one.
+            let run_len = find_whitespace(&self[read..]).unwrap_or(self.len() - read);
+            self.copy_within(read..read + run_len, write);
+            read += run_len;
+            write += run_len;
+            if read < self.len() {
+                self[write] = b' ';
+                write += 1;
+                read = find_non_whitespace(&self[read..]).map_or(self.len(), |i| read + i);
+            }
+        }
+        write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IsAscii2;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_bytes(rng: &mut StdRng) -> ([u8; 100], usize) {
+        // Half of the inputs are ASCII, and half of the rest are mostly whitespace.
+        let bytes = match rng.gen_range(0..4) {
+            0 | 1 => core::array::from_fn(|_| rng.gen_range(0..128)),
+            2 => core::array::from_fn(|_| rng.gen()),
+            _ => core::array::from_fn(|_| b" \t\n\x0B\x0C\rab\x80"[rng.gen_range(0..9)]),
+        };
+        (bytes, rng.gen_range(0..=100))
+    }
+
+    macro_rules! against_naive {
+        ($($test: ident: $replace: ident == $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    let mut rng = StdRng::seed_from_u64(0);
+                    for _ in 0..1000 {
+                        let (bytes, len) = random_bytes(&mut rng);
+                        let substitute = rng.gen();
+                        let mut expected = bytes;
+                        for x in &mut expected[..len] {
+                            if x.$method() {
+                                *x = substitute;
+                            }
+                        }
+                        let mut actual = bytes;
+                        actual[..len].$replace(substitute);
+                        assert_eq!(actual, expected, "Failed on {:?}", bytes);
+                    }
+                }
+            )*
+        };
+    }
+
+    against_naive! {
+        ascii: replace_ascii_2 == is_ascii_2,
+        alphabetic: replace_ascii_alphabetic_2 == is_ascii_alphabetic_2,
+        alphanumeric: replace_ascii_alphanumeric_2 == is_ascii_alphanumeric_2,
+        control: replace_ascii_control_2 == is_ascii_control_2,
+        digit: replace_ascii_digit_2 == is_ascii_digit_2,
+        graphic: replace_ascii_graphic_2 == is_ascii_graphic_2,
+        hexdigit: replace_ascii_hexdigit_2 == is_ascii_hexdigit_2,
+        lowercase: replace_ascii_lowercase_2 == is_ascii_lowercase_2,
+        punctuation: replace_ascii_punctuation_2 == is_ascii_punctuation_2,
+        uppercase: replace_ascii_uppercase_2 == is_ascii_uppercase_2,
+        whitespace: replace_ascii_whitespace_2 == is_ascii_whitespace_2,
+    }
+
+    #[test]
+    fn custom_class() {
+        use crate::http::HttpFieldValueByte;
+
+        let mut bytes = *b"a\tb\r\nc\x00d\x7F\xFF";
+        bytes.replace_class_2(HttpFieldValueByte, b'.');
+        assert_eq!(&bytes, b"...\r\n.\x00.\x7F.");
+    }
+
+    #[test]
+    fn collapse_whitespace() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10000 {
+            let (bytes, len) = random_bytes(&mut rng);
+            let bytes = &bytes[..len];
+
+            let mut expected = [0; 100];
+            let mut expected_len = 0;
+            for (i, &x) in bytes.iter().enumerate() {
+                if !x.is_ascii_whitespace_2() {
+                    expected[expected_len] = x;
+                    expected_len += 1;
+                } else if i == 0 || !bytes[i - 1].is_ascii_whitespace_2() {
+                    expected[expected_len] = b' ';
+                    expected_len += 1;
+                }
+            }
+
+            let mut actual = [0; 100];
+            actual[..len].copy_from_slice(bytes);
+            let actual_len = actual[..len].collapse_ascii_whitespace_2();
+            assert_eq!(
+                &actual[..actual_len],
+                &expected[..expected_len],
+                "Failed on {:?}",
+                bytes
+            );
+        }
+    }
+
+    #[test]
+    fn collapse_examples() {
+        let mut bytes = *b"  hello, \t\r\n world\x0B!\n";
+        let len = bytes.collapse_ascii_whitespace_2();
+        assert_eq!(&bytes[..len], b" hello, world\x0B! ");
+
+        let mut bytes = [];
+        assert_eq!(bytes.collapse_ascii_whitespace_2(), 0);
+    }
+}
@@ -0,0 +1,242 @@
+//! Splitting text into maximal runs of bytes with the same class, for syntax
+//! highlighting and tokenizing.
+//!
+//! A byte's class is the first entry of a caller-supplied priority list that shares
+//! a class with the byte's `AsciiClasses`, or no class if none does. Each byte's
+//! classes are computed once.
+
+use core::str;
+
+use crate::flags::AsciiClasses;
+
+/// A maximal run of bytes with the same class.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Run<'a, T: ?Sized> {
+    /// The entry of the priority list that the bytes matched, or `None` if they
+    /// matched no entry.
+    pub class: Option<AsciiClasses>,
+    pub text: &'a T,
+}
+
+// Not derived, since that would require `T: Copy`.
+impl<T: ?Sized> Clone for Run<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Run<'_, T> {}
+
+/// An iterator over the runs of a byte slice. See `runs`.
+#[derive(Clone, Debug)]
+pub struct Runs<'a, 'p> {
+    bytes: &'a [u8],
+    priority: &'p [AsciiClasses],
+    // The class index of the first byte of `bytes`, if it was already computed while
+    // finding the end of the previous run.
+    first_index: Option<usize>,
+}
+
+/// Returns an iterator over the maximal runs of `bytes` whose bytes match the same
+/// entry of `priority`.
+///
+/// ```
+/// use is_ascii_speedups::flags::AsciiClasses;
+/// use is_ascii_speedups::runs::runs;
+///
+/// let priority = [AsciiClasses::DIGIT, AsciiClasses::ALPHABETIC];
+/// let texts: Vec<_> = runs(b"x1 = 42;", &priority).map(|run| run.text).collect();
+/// assert_eq!(texts, [&b"x"[..], b"1", b" = ", b"42", b";"]);
+/// ```
+#[must_use]
+#[inline]
+pub fn runs<'a, 'p>(bytes: &'a [u8], priority: &'p [AsciiClasses]) -> Runs<'a, 'p> {
+    Runs {
+        bytes,
+        priority,
+        first_index: None,
+    }
+}
+
+/// Returns the index in `priority` of the class of `x`, or `priority.len()` if it has
+/// none.
+#[inline]
+fn class_index(x: u8, priority: &[AsciiClasses]) -> usize {
+    let classes = AsciiClasses::of(x);
+    priority
+        .iter()
+        .position(|&class| classes.intersects(class))
+        .unwrap_or(priority.len())
+}
+
+impl<'a> Iterator for Runs<'a, '_> {
+    type Item = Run<'a, [u8]>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&first, rest) = self.bytes.split_first()?;
+        let index = match self.first_index.take() {
+            Some(index) => index,
+            None => class_index(first, self.priority),
+        };
+        let mut len = 1;
+        for &x in rest {
+            let x_index = class_index(x, self.priority);
+            if x_index != index {
+                self.first_index = Some(x_index);
+                break;
+            }
+            len += 1;
+        }
+        let (text, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(Run {
+            class: self.priority.get(index).copied(),
+            text,
+        })
+    }
+}
+
+impl core::iter::FusedIterator for Runs<'_, '_> {}
+
+/// An iterator over the runs of a `str`. See `str_runs`.
+#[derive(Clone, Debug)]
+pub struct StrRuns<'a, 'p> {
+    runs: Runs<'a, 'p>,
+}
+
+/// Returns an iterator over the maximal runs of `s` whose bytes match the same entry
+/// of `priority`.
+///
+/// Bytes from 128 up are in no class, so every byte of a non-ASCII `char` is in the
+/// same run, and runs always start and end on `char` boundaries.
+#[must_use]
+#[inline]
+pub fn str_runs<'a, 'p>(s: &'a str, priority: &'p [AsciiClasses]) -> StrRuns<'a, 'p> {
+    StrRuns {
+        runs: runs(s.as_bytes(), priority),
+    }
+}
+
+impl<'a> Iterator for StrRuns<'a, '_> {
+    type Item = Run<'a, str>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let Run { class, text } = self.runs.next()?;
+        Some(Run {
+            class,
+            // SAFETY: A run boundary is between two bytes with different classes, so
+            // at least one of them has a class and so is ASCII, and so the boundary
+            // is on a `char` boundary.
+            text: unsafe { str::from_utf8_unchecked(text) },
+        })
+    }
+}
+
+impl core::iter::FusedIterator for StrRuns<'_, '_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const TOKENS: [AsciiClasses; 4] = [
+        AsciiClasses::DIGIT,
+        AsciiClasses::ALPHABETIC,
+        AsciiClasses::WHITESPACE,
+        AsciiClasses::PUNCTUATION,
+    ];
+
+    // Checks that the runs concatenate to `bytes`, that every byte of a run has the
+    // run's class, and that neighbouring runs have different classes.
+    fn check(bytes: &[u8], priority: &[AsciiClasses]) {
+        let mut len = 0;
+        let mut previous = None;
+        for run in runs(bytes, priority) {
+            assert!(!run.text.is_empty());
+            assert_eq!(run.text, &bytes[len..len + run.text.len()]);
+            len += run.text.len();
+            for &x in run.text {
+                let expected = priority
+                    .iter()
+                    .copied()
+                    .find(|&class| AsciiClasses::of(x).intersects(class));
+                assert_eq!(run.class, expected, "Failed on {:?}", bytes);
+            }
+            assert!(previous != Some(run.class), "Failed on {:?}", bytes);
+            previous = Some(run.class);
+        }
+        assert_eq!(len, bytes.len());
+    }
+
+    #[test]
+    fn random_bytes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let len = rng.gen_range(0..64);
+            let bytes: [u8; 64] = core::array::from_fn(|_| rng.gen());
+            check(&bytes[..len], &TOKENS);
+            check(&bytes[..len], &[AsciiClasses::ASCII]);
+            check(&bytes[..len], &[]);
+        }
+    }
+
+    #[test]
+    fn random_ascii_text() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let len = rng.gen_range(0..64);
+            let bytes: [u8; 64] = core::array::from_fn(|_| rng.gen_range(0..128));
+            check(&bytes[..len], &TOKENS);
+            check(
+                &bytes[..len],
+                &[AsciiClasses::HEXDIGIT, AsciiClasses::ALPHABETIC],
+            );
+        }
+    }
+
+    #[test]
+    fn priority_order() {
+        let hex_first = [AsciiClasses::HEXDIGIT, AsciiClasses::ALPHABETIC];
+        let mut iter = runs(b"cafe babe", &hex_first);
+        assert_eq!(
+            iter.next(),
+            Some(Run {
+                class: Some(AsciiClasses::HEXDIGIT),
+                text: &b"cafe"[..],
+            })
+        );
+
+        let alpha_first = [AsciiClasses::ALPHABETIC, AsciiClasses::HEXDIGIT];
+        let texts = runs(b"x0fz", &alpha_first).map(|run| run.text);
+        assert!(texts.eq([&b"x"[..], b"0", b"fz"]));
+    }
+
+    #[test]
+    fn str() {
+        let s = "let caf\u{e9} = \u{1F600}42;";
+        let texts = str_runs(s, &TOKENS).map(|run| run.text);
+        assert!(texts.eq([
+            "let",
+            " ",
+            "caf",
+            "\u{e9}",
+            " ",
+            "=",
+            " ",
+            "\u{1F600}",
+            "42",
+            ";"
+        ]));
+
+        let mut concatenated = 0;
+        for run in str_runs(s, &[AsciiClasses::ASCII]) {
+            assert!(s[concatenated..].starts_with(run.text));
+            concatenated += run.text.len();
+        }
+        assert_eq!(concatenated, s.len());
+    }
+}
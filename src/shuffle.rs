@@ -0,0 +1,452 @@
+//! Classifying 16 or 32 bytes at a time with `pshufb` nibble lookups. This works
+//! for any set of bytes, including ones that fit none of the branchless tricks
+//! `IsAscii2` uses.
+//!
+//! A byte's low nibble looks up one table and its high nibble looks up another.
+//! The byte is in the set iff the two entries share a set bit. Each high nibble has a
+//! row, the low nibbles that are in the set after it, and high nibbles with the same
+//! row share a bit, so the tables are exact for any set with at most eight distinct
+//! nonempty rows. That covers ASCII sets, which only have eight rows, as well as sets
+//! like the UTF-8 lead bytes or the Latin-1 letters. Sets with more rows are scanned
+//! a byte at a time with a bitmap instead.
+//!
+//! AVX2 and SSSE3 are detected at runtime with `cpuid`, which needs no `std`.
+//! Elsewhere the bitmap is used for every set.
+
+use crate::bracket::ByteSet;
+use crate::class::AsciiClass;
+use crate::cpu;
+
+/// A set of bytes as nibble lookup tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NibbleClass {
+    /// Entry `l` has the bit of every row that has the low nibble `l`.
+    low: [u8; 16],
+    /// Entry `h` is the bit of the row of the high nibble `h`, or 0 if it's empty.
+    high: [u8; 16],
+    /// The set, with bit `x % 128` of entry `x / 128` set iff `x` is in it.
+    bits: [u128; 2],
+    /// Whether the set has at most eight distinct nonempty rows, so that the tables
+    /// are exact.
+    vectorized: bool,
+}
+
+impl NibbleClass {
+    /// Builds the tables for the set `bits`, where bit `x % 128` of `bits[x / 128]` is
+    /// set iff `x` is in it.
+    ///
+    /// ```
+    /// use is_ascii_speedups::shuffle::NibbleClass;
+    ///
+    /// // The UTF-8 lead bytes of multibyte sequences, `0xC2..=0xF4`.
+    /// let lead = NibbleClass::new([0, (u128::MAX << 0x42) & (u128::MAX >> (127 - 0x74))]);
+    /// assert!(lead.is_vectorized());
+    /// assert_eq!(lead.find_member("caf\u{E9}".as_bytes()), Some(3));
+    /// ```
+    #[must_use]
+    pub const fn new(bits: [u128; 2]) -> Self {
+        let mut rows = [0u16; 16];
+        let mut x = 0;
+        while x < 256 {
+            if (bits[x / 128] >> (x % 128)) & 1 != 0 {
+                rows[x >> 4] |= 1 << (x & 15);
+            }
+            x += 1;
+        }
+
+        // Each distinct nonempty row gets the next bit, and its high nibbles share it.
+        let mut distinct = [0u16; 8];
+        let mut count = 0;
+        let mut low = [0; 16];
+        let mut high = [0; 16];
+        let mut h = 0;
+        while h < 16 {
+            let row = rows[h];
+            if row != 0 {
+                let mut i = 0;
+                while i < count && distinct[i] != row {
+                    i += 1;
+                }
+                if i == count {
+                    if count == distinct.len() {
+                        return NibbleClass {
+                            low: [0; 16],
+                            high: [0; 16],
+                            bits,
+                            vectorized: false,
+                        };
+                    }
+                    distinct[count] = row;
+                    count += 1;
+                    let mut l = 0;
+                    while l < 16 {
+                        if (row >> l) & 1 != 0 {
+                            low[l] |= 1 << i;
+                        }
+                        l += 1;
+                    }
+                }
+                high[h] = 1 << i;
+            }
+            h += 1;
+        }
+        NibbleClass {
+            low,
+            high,
+            bits,
+            vectorized: true,
+        }
+    }
+
+    /// `new` for a `ByteSet`.
+    #[must_use]
+    pub const fn from_byte_set(set: ByteSet) -> Self {
+        let high = if set.contains_high() { u128::MAX } else { 0 };
+        NibbleClass::new([set.ascii_bits(), high])
+    }
+
+    /// Returns `true` iff the scans use the nibble lookups, rather than the bitmap a
+    /// byte at a time, because the set has at most eight distinct nonempty rows.
+    #[must_use]
+    #[inline]
+    pub const fn is_vectorized(&self) -> bool {
+        self.vectorized
+    }
+
+    /// Returns the index of the first byte of `bytes` that is in this class, or
+    /// `None` if no byte is.
+    #[inline]
+    pub fn find_member(&self, bytes: &[u8]) -> Option<usize> {
+        self.find(bytes, true)
+    }
+
+    #[inline]
+    fn find(&self, bytes: &[u8], members: bool) -> Option<usize> {
+        if !self.vectorized {
+            return self.find_scalar(bytes, members);
+        }
+        match cpu::level() {
+            // SAFETY: `level` only returns a level the CPU supports.
+            #[cfg(target_arch = "x86_64")]
+            cpu::Level::Avx2 => unsafe { self.find_avx2(bytes, members) },
+            #[cfg(target_arch = "x86_64")]
+            cpu::Level::Ssse3 => unsafe { self.find_ssse3(bytes, members) },
+            _ => self.find_scalar(bytes, members),
+        }
+    }
+
+    fn find_scalar(&self, bytes: &[u8], members: bool) -> Option<usize> {
+        bytes.iter().position(|&x| self.contains(x) == members)
+    }
+
+    // Only for vectorized classes.
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "ssse3")]
+    unsafe fn find_ssse3(&self, bytes: &[u8], members: bool) -> Option<usize> {
+        use core::arch::x86_64::*;
+
+        let low_table = _mm_loadu_si128(self.low.as_ptr().cast());
+        let high_table = _mm_loadu_si128(self.high.as_ptr().cast());
+        let nibble = _mm_set1_epi8(0x0F);
+        let flip = if members { 0 } else { 0xFFFF };
+
+        let mut i = 0;
+        while i + 16 <= bytes.len() {
+            let x = _mm_loadu_si128(bytes.as_ptr().add(i).cast());
+            let low = _mm_shuffle_epi8(low_table, _mm_and_si128(x, nibble));
+            // There's no byte shift, so the 16-bit shift drags in bits of the next
+            // byte, which the mask clears.
+            let high = _mm_shuffle_epi8(high_table, _mm_and_si128(_mm_srli_epi16(x, 4), nibble));
+            let nonmembers = _mm_movemask_epi8(_mm_cmpeq_epi8(
+                _mm_and_si128(low, high),
+                _mm_setzero_si128(),
+            ));
+            let found = (!nonmembers as u32 & 0xFFFF) ^ flip;
+            if found != 0 {
+                return Some(i + found.trailing_zeros() as usize);
+            }
+            i += 16;
+        }
+        self.find_scalar(&bytes[i..], members).map(|j| i + j)
+    }
+
+    // Only for vectorized classes.
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn find_avx2(&self, bytes: &[u8], members: bool) -> Option<usize> {
+        use core::arch::x86_64::*;
+
+        // `vpshufb` looks up each 128-bit lane separately, so both lanes get the
+        // tables.
+        let low_table = _mm256_broadcastsi128_si256(_mm_loadu_si128(self.low.as_ptr().cast()));
+        let high_table = _mm256_broadcastsi128_si256(_mm_loadu_si128(self.high.as_ptr().cast()));
+        let nibble = _mm256_set1_epi8(0x0F);
+        let flip = if members { 0 } else { u32::MAX };
+
+        let mut i = 0;
+        while i + 32 <= bytes.len() {
+            let x = _mm256_loadu_si256(bytes.as_ptr().add(i).cast());
+            let low = _mm256_shuffle_epi8(low_table, _mm256_and_si256(x, nibble));
+            let high = _mm256_shuffle_epi8(
+                high_table,
+                _mm256_and_si256(_mm256_srli_epi16(x, 4), nibble),
+            );
+            let nonmembers = _mm256_movemask_epi8(_mm256_cmpeq_epi8(
+                _mm256_and_si256(low, high),
+                _mm256_setzero_si256(),
+            ));
+            let found = !nonmembers as u32 ^ flip;
+            if found != 0 {
+                return Some(i + found.trailing_zeros() as usize);
+            }
+            i += 32;
+        }
+        // The rest is shorter than 32 bytes, but may have 16 for SSSE3.
+        self.find_ssse3(&bytes[i..], members).map(|j| i + j)
+    }
+}
+
+impl AsciiClass for NibbleClass {
+    /// A bitmap lookup, which is exact for every set, unlike the tables.
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        (self.bits[(x >> 7) as usize] >> (x & 127)) & 1 != 0
+    }
+
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        self.find(bytes, false)
+    }
+}
+
+impl From<ByteSet> for NibbleClass {
+    #[inline]
+    fn from(set: ByteSet) -> Self {
+        NibbleClass::from_byte_set(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::vec::Vec;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Whether `x` is in the set `bits`, as `NibbleClass::new` takes it.
+    fn member(bits: [u128; 2], x: u8) -> bool {
+        (bits[(x >> 7) as usize] >> (x & 127)) & 1 != 0
+    }
+
+    // Each high nibble gets one of a few random rows, or none, so the number of
+    // distinct rows is sometimes more than eight.
+    fn random_set(rng: &mut StdRng) -> [u128; 2] {
+        let rows: Vec<u16> = (0..rng.gen_range(1..=16)).map(|_| rng.gen()).collect();
+        let mut bits = [0; 2];
+        for h in 0..16 {
+            if rng.gen_ratio(3, 4) {
+                let row = rows[rng.gen_range(0..rows.len())] as u128;
+                bits[h / 8] |= row << (h % 8 * 16);
+            }
+        }
+        bits
+    }
+
+    fn random_byte_set(rng: &mut StdRng) -> ByteSet {
+        let ascii = match rng.gen_range(0..3) {
+            0 => rng.gen::<u128>() & rng.gen::<u128>(),
+            1 => rng.gen::<u128>(),
+            _ => rng.gen::<u128>() | rng.gen::<u128>(),
+        };
+        let set = ByteSet::from_ascii_bits(ascii);
+        if rng.gen() {
+            set | !ByteSet::from_ascii_bits(u128::MAX)
+        } else {
+            set
+        }
+    }
+
+    // Every backend this CPU can run, each checked against the scalar membership.
+    fn finds(class: &NibbleClass, bytes: &[u8], members: bool) -> Vec<Option<usize>> {
+        let mut found = alloc::vec![class.find_scalar(bytes, members)];
+        #[cfg(target_arch = "x86_64")]
+        if class.is_vectorized() {
+            if cpu::level() >= cpu::Level::Ssse3 {
+                found.push(unsafe { class.find_ssse3(bytes, members) });
+            }
+            if cpu::level() == cpu::Level::Avx2 {
+                found.push(unsafe { class.find_avx2(bytes, members) });
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn contains_matches_set() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let set = random_set(&mut rng);
+            let class = NibbleClass::new(set);
+            for x in 0..=u8::MAX {
+                assert!(class.contains(x) == member(set, x), "Failed on {}", x);
+            }
+
+            let set = random_byte_set(&mut rng);
+            let class = NibbleClass::from(set);
+            for x in 0..=u8::MAX {
+                assert!(class.contains(x) == set.contains(x), "Failed on {}", x);
+            }
+        }
+    }
+
+    // The tables agree with the set for every byte, whenever they're used.
+    #[test]
+    fn tables_match_set() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut vectorized = 0;
+        for _ in 0..1000 {
+            let set = random_set(&mut rng);
+            let class = NibbleClass::new(set);
+            if class.is_vectorized() {
+                vectorized += 1;
+                for x in 0..=u8::MAX {
+                    let found = class.low[(x & 15) as usize] & class.high[(x >> 4) as usize];
+                    assert!((found != 0) == member(set, x), "Failed on {}", x);
+                }
+            }
+        }
+        // Both kinds of set come up.
+        assert!(
+            vectorized > 100 && vectorized < 900,
+            "{} vectorized",
+            vectorized
+        );
+    }
+
+    // The set of the bytes in `ranges`, which are inclusive.
+    fn ranges(ranges: &[(u8, u8)]) -> [u128; 2] {
+        let mut bits = [0; 2];
+        for &(first, last) in ranges {
+            for x in first..=last {
+                bits[(x >> 7) as usize] |= 1 << (x & 127);
+            }
+        }
+        bits
+    }
+
+    // Sets whose high halves aren't all in or all out.
+    fn named_sets() -> [[u128; 2]; 4] {
+        [
+            // UTF-8 lead bytes of multibyte sequences.
+            ranges(&[(0xC2, 0xF4)]),
+            // UTF-8 continuation bytes.
+            ranges(&[(0x80, 0xBF)]),
+            // Latin-1 letters.
+            ranges(&[
+                (b'A', b'Z'),
+                (b'a', b'z'),
+                (0xAA, 0xAA),
+                (0xB5, 0xB5),
+                (0xBA, 0xBA),
+                (0xC0, 0xD6),
+                (0xD8, 0xF6),
+                (0xF8, 0xFF),
+            ]),
+            // Latin-1 whitespace.
+            ranges(&[(b'\t', b'\r'), (b' ', b' '), (0x85, 0x85), (0xA0, 0xA0)]),
+        ]
+    }
+
+    #[test]
+    fn named_sets_are_vectorized() {
+        for set in named_sets() {
+            assert!(NibbleClass::new(set).is_vectorized(), "Failed on {:?}", set);
+        }
+    }
+
+    // A set with 16 distinct rows, `0x00`, `0x11`, ..., `0xFF`, falls back to the bitmap.
+    #[test]
+    fn too_many_rows() {
+        let diagonal = ranges(&core::array::from_fn::<_, 16, _>(|h| {
+            let x = h as u8 * 0x11;
+            (x, x)
+        }));
+        let class = NibbleClass::new(diagonal);
+        assert!(!class.is_vectorized());
+        assert_eq!(class.find_member(&[0x12; 40]), None);
+        assert_eq!(class.find_nonmember(&[0x77; 40]), None);
+        let mut bytes = [0x33; 40];
+        bytes[37] = 0x34;
+        assert_eq!(class.find_nonmember(&bytes), Some(37));
+    }
+
+    #[test]
+    fn every_byte_at_every_lane() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let random_sets = core::iter::repeat_with(|| random_set(&mut rng))
+            .filter(|&set| NibbleClass::new(set).is_vectorized())
+            .take(20);
+        for set in named_sets().into_iter().chain(random_sets) {
+            let class = NibbleClass::new(set);
+            let filler = (0..=u8::MAX).find(|&y| member(set, y)).unwrap();
+            let other = (0..=u8::MAX).find(|&y| !member(set, y)).unwrap();
+            for x in 0..=u8::MAX {
+                for index in 0..48 {
+                    let mut bytes = [filler; 48];
+                    bytes[index] = x;
+                    let expected = bytes.iter().position(|&y| !member(set, y));
+                    for found in finds(&class, &bytes, false) {
+                        assert_eq!(found, expected, "Failed on {} at {}", x, index);
+                    }
+
+                    let mut bytes = [other; 48];
+                    bytes[index] = x;
+                    let expected = bytes.iter().position(|&y| member(set, y));
+                    for found in finds(&class, &bytes, true) {
+                        assert_eq!(found, expected, "Failed on {} at {}", x, index);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_buffers() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..10_000 {
+            let set = random_set(&mut rng);
+            let class = NibbleClass::new(set);
+            let len = rng.gen_range(0..100);
+            // Mostly members, so that the first nonmember is often past the first
+            // vector.
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| loop {
+                    let x = rng.gen();
+                    if member(set, x) || rng.gen_ratio(1, 20) {
+                        break x;
+                    }
+                })
+                .collect();
+            let expected = bytes.iter().position(|&y| !member(set, y));
+            assert_eq!(class.find_nonmember(&bytes), expected);
+            assert_eq!(class.contains_all(&bytes), expected.is_none());
+            for found in finds(&class, &bytes, false) {
+                assert_eq!(found, expected);
+            }
+            let expected = bytes.iter().position(|&y| member(set, y));
+            assert_eq!(class.find_member(&bytes), expected);
+        }
+    }
+
+    #[test]
+    fn parsed_sets() {
+        let set: ByteSet = "[[:alnum:]_.-]".parse().unwrap();
+        let class = NibbleClass::from(set);
+        assert_eq!(class.find_nonmember(b"config_key.name-2 = 1"), Some(17));
+        assert_eq!(class.find_member(b"  \t= x"), Some(5));
+        let not_space = NibbleClass::from_byte_set("[^[:space:]]".parse().unwrap());
+        assert_eq!(not_space.find_member("   \u{E9}".as_bytes()), Some(3));
+    }
+}
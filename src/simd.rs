@@ -0,0 +1,242 @@
+//! The `IsAscii2` classes over `core::simd` vectors, for one vector path that works
+//! on every architecture.
+//!
+//! Each class tests every lane with the same ranges as the `u8` version, the way
+//! the `swar` masks of `class` do for words. The strip tables of
+//! `handle_strip_of_each_chunk!` would need a lane-wise table lookup, so the classes
+//! that use them are built from the others instead.
+
+use core::simd::prelude::*;
+
+// `x.wrapping_sub(start) < len` in every lane. `Simd`'s subtraction wraps.
+#[inline]
+fn in_range<const N: usize>(x: Simd<u8, N>, start: u8, len: u8) -> Mask<i8, N> {
+    (x - Simd::splat(start)).simd_lt(Simd::splat(len))
+}
+
+/// The lanes of `x` for which `is_ascii_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    x.simd_lt(Simd::splat(128))
+}
+
+/// The lanes of `x` for which `is_ascii_alphabetic_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_alphabetic_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    // The same `| 0b0010_0000` case fold as `is_ascii_alphabetic_2`.
+    in_range(x | Simd::splat(0b0010_0000), b'a', 26)
+}
+
+/// The lanes of `x` for which `is_ascii_alphanumeric_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_alphanumeric_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    is_ascii_alphabetic_simd(x) | is_ascii_digit_simd(x)
+}
+
+/// The lanes of `x` for which `is_ascii_control_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_control_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    x.simd_lt(Simd::splat(b' ')) | x.simd_eq(Simd::splat(127))
+}
+
+/// The lanes of `x` for which `is_ascii_digit_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_digit_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    in_range(x, b'0', 10)
+}
+
+/// The lanes of `x` for which `is_ascii_graphic_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_graphic_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    in_range(x, b'!', 94)
+}
+
+/// The lanes of `x` for which `is_ascii_hexdigit_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_hexdigit_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    is_ascii_digit_simd(x) | in_range(x | Simd::splat(0b0010_0000), b'a', 6)
+}
+
+/// The lanes of `x` for which `is_ascii_lowercase_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_lowercase_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    in_range(x, b'a', 26)
+}
+
+/// The lanes of `x` for which `is_ascii_punctuation_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_punctuation_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    is_ascii_graphic_simd(x) & !is_ascii_alphanumeric_simd(x)
+}
+
+/// The lanes of `x` for which `is_ascii_uppercase_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_uppercase_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    in_range(x, b'A', 26)
+}
+
+/// The lanes of `x` for which `is_ascii_whitespace_2` is true.
+#[must_use]
+#[inline]
+pub fn is_ascii_whitespace_simd<const N: usize>(x: Simd<u8, N>) -> Mask<i8, N> {
+    // Tab and line feed, then form feed and carriage return, are next to each other.
+    x.simd_eq(Simd::splat(b' ')) | in_range(x, b'\t', 2) | in_range(x, b'\x0C', 2)
+}
+
+// The bits of the lanes of `matches` that are in `chunk`, and a mask of those lanes,
+// for a chunk that is padded with zeros if it's shorter than `N`. `N` is at most 64,
+// since that's as wide as `to_bitmask` goes.
+#[inline]
+fn chunk_matches<const N: usize>(
+    matches: impl Fn(Simd<u8, N>) -> Mask<i8, N>,
+    chunk: &[u8],
+) -> (u64, u64) {
+    let len = chunk.len();
+    let lanes = u64::MAX >> (64 - len);
+    let x = if len == N {
+        Simd::from_slice(chunk)
+    } else {
+        Simd::load_or_default(chunk)
+    };
+    (matches(x).to_bitmask() & lanes, lanes)
+}
+
+/// Returns the index of the first byte of `bytes` for which `matches` is false, or
+/// `None` if it's true for every byte. The bytes are tested `N` at a time, and `N`
+/// must be at most 64.
+///
+/// ```
+/// use is_ascii_speedups::simd::{find_nonmember, is_ascii_alphanumeric_simd};
+///
+/// let bytes = b"user_name";
+/// assert_eq!(find_nonmember::<16>(bytes, is_ascii_alphanumeric_simd), Some(4));
+/// ```
+#[inline]
+pub fn find_nonmember<const N: usize>(
+    bytes: &[u8],
+    matches: impl Fn(Simd<u8, N>) -> Mask<i8, N>,
+) -> Option<usize> {
+    const { assert!(N <= 64, "`to_bitmask` only goes up to 64 lanes") };
+    bytes.chunks(N).enumerate().find_map(|(i, chunk)| {
+        let (members, lanes) = chunk_matches(&matches, chunk);
+        let nonmembers = !members & lanes;
+        (nonmembers != 0).then(|| i * N + nonmembers.trailing_zeros() as usize)
+    })
+}
+
+/// Returns `true` iff `matches` is true for every byte of `bytes`. The bytes are
+/// tested `N` at a time, and `N` must be at most 64.
+#[inline]
+pub fn contains_all<const N: usize>(
+    bytes: &[u8],
+    matches: impl Fn(Simd<u8, N>) -> Mask<i8, N>,
+) -> bool {
+    find_nonmember(bytes, matches).is_none()
+}
+
+/// Returns how many bytes of `bytes` `matches` is true for. The bytes are tested `N`
+/// at a time, and `N` must be at most 64.
+#[inline]
+pub fn count<const N: usize>(bytes: &[u8], matches: impl Fn(Simd<u8, N>) -> Mask<i8, N>) -> usize {
+    const { assert!(N <= 64, "`to_bitmask` only goes up to 64 lanes") };
+    bytes
+        .chunks(N)
+        .map(|chunk| chunk_matches(&matches, chunk).0.count_ones() as usize)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::IsAscii2;
+    use alloc::vec::Vec;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Every byte value, `N` lanes at a time, against the `u8` version.
+    fn check_lanes<const N: usize>(matches: fn(Simd<u8, N>) -> Mask<i8, N>, is: fn(&u8) -> bool) {
+        for start in (0..256).step_by(N) {
+            let x = Simd::from_array(core::array::from_fn(|i| (start + i) as u8));
+            let mask = matches(x);
+            for i in 0..N {
+                assert!(mask.test(i) == is(&x[i]), "Failed on {}", x[i]);
+            }
+        }
+    }
+
+    macro_rules! lanes {
+        ($($test: ident: $simd: ident => $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    check_lanes::<1>($simd, u8::$method);
+                    check_lanes::<4>($simd, u8::$method);
+                    check_lanes::<16>($simd, u8::$method);
+                    check_lanes::<32>($simd, u8::$method);
+                    check_lanes::<64>($simd, u8::$method);
+                }
+            )*
+        };
+    }
+
+    lanes! {
+        ascii_lanes: is_ascii_simd => is_ascii_2,
+        alphabetic_lanes: is_ascii_alphabetic_simd => is_ascii_alphabetic_2,
+        alphanumeric_lanes: is_ascii_alphanumeric_simd => is_ascii_alphanumeric_2,
+        control_lanes: is_ascii_control_simd => is_ascii_control_2,
+        digit_lanes: is_ascii_digit_simd => is_ascii_digit_2,
+        graphic_lanes: is_ascii_graphic_simd => is_ascii_graphic_2,
+        hexdigit_lanes: is_ascii_hexdigit_simd => is_ascii_hexdigit_2,
+        lowercase_lanes: is_ascii_lowercase_simd => is_ascii_lowercase_2,
+        punctuation_lanes: is_ascii_punctuation_simd => is_ascii_punctuation_2,
+        uppercase_lanes: is_ascii_uppercase_simd => is_ascii_uppercase_2,
+        whitespace_lanes: is_ascii_whitespace_simd => is_ascii_whitespace_2,
+    }
+
+    #[test]
+    fn drivers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0..200);
+            // Mostly alphanumerics, so that the first nonmember is often past the first
+            // vector.
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| loop {
+                    let x: u8 = rng.gen();
+                    if x.is_ascii_alphanumeric_2() || rng.gen_ratio(1, 50) {
+                        break x;
+                    }
+                })
+                .collect();
+            let expected = bytes.iter().position(|x| !x.is_ascii_alphanumeric_2());
+            let expected_count = bytes.iter().filter(|x| x.is_ascii_digit_2()).count();
+            assert_eq!(
+                find_nonmember::<8>(&bytes, is_ascii_alphanumeric_simd),
+                expected
+            );
+            assert_eq!(
+                find_nonmember::<16>(&bytes, is_ascii_alphanumeric_simd),
+                expected
+            );
+            assert_eq!(
+                find_nonmember::<64>(&bytes, is_ascii_alphanumeric_simd),
+                expected
+            );
+            assert!(contains_all::<32>(&bytes, is_ascii_alphanumeric_simd) == expected.is_none());
+            assert_eq!(count::<16>(&bytes, is_ascii_digit_simd), expected_count);
+            assert_eq!(count::<64>(&bytes, is_ascii_digit_simd), expected_count);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Splitting off the longest prefix of a class, the "consume while the class
+//! matches" step of a lexer.
+//!
+//! The split point is found with `AsciiClass::find_nonmember`, which scans a word at
+//! a time for the `IsAscii2` classes and any other class that provides a word mask.
+
+use crate::class::*;
+use crate::AsciiClass;
+
+pub trait SplitWhileAscii2 {
+    /// Splits `self` into its longest prefix of bytes in `class` and the rest.
+    ///
+    /// A `str` is split on a byte boundary, then, if that's inside a `char`, moved
+    /// back to the start of that `char`. That only happens for classes with bytes
+    /// from 128 up, and leaves a `char` that's partly in `class` in the rest.
+    fn split_while_2<C: AsciiClass>(&self, class: C) -> (&Self, &Self);
+
+    #[inline]
+    fn split_while_ascii_2(&self) -> (&Self, &Self) {
+        self.split_while_2(Ascii)
+    }
+
+    #[inline]
+    fn split_while_ascii_alphabetic_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiAlphabetic)
+    }
+
+    #[inline]
+    fn split_while_ascii_alphanumeric_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiAlphanumeric)
+    }
+
+    #[inline]
+    fn split_while_ascii_control_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiControl)
+    }
+
+    #[inline]
+    fn split_while_ascii_digit_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiDigit)
+    }
+
+    #[inline]
+    fn split_while_ascii_graphic_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiGraphic)
+    }
+
+    #[inline]
+    fn split_while_ascii_hexdigit_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiHexdigit)
+    }
+
+    #[inline]
+    fn split_while_ascii_lowercase_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiLowercase)
+    }
+
+    #[inline]
+    fn split_while_ascii_punctuation_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiPunctuation)
+    }
+
+    #[inline]
+    fn split_while_ascii_uppercase_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiUppercase)
+    }
+
+    #[inline]
+    fn split_while_ascii_whitespace_2(&self) -> (&Self, &Self) {
+        self.split_while_2(AsciiWhitespace)
+    }
+}
+
+impl SplitWhileAscii2 for [u8] {
+    #[inline]
+    fn split_while_2<C: AsciiClass>(&self, class: C) -> (&Self, &Self) {
+        let i = class.find_nonmember(self).unwrap_or(self.len());
+        self.split_at(i)
+    }
+}
+
+impl SplitWhileAscii2 for str {
+    #[inline]
+    fn split_while_2<C: AsciiClass>(&self, class: C) -> (&Self, &Self) {
+        let mut i = class.find_nonmember(self.as_bytes()).unwrap_or(self.len());
+        while !self.is_char_boundary(i) {
+            i -= 1;
+        }
+        self.split_at(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IsAscii2;
+
+    macro_rules! against_take_while {
+        ($($test: ident: $split: ident == $method: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    let member = (0..=u8::MAX).find(|y| y.$method()).unwrap();
+                    for x in 0..=u8::MAX {
+                        for len in 0..=24 {
+                            let mut bytes = [0; 25];
+                            bytes[..len].fill(member);
+                            bytes[len] = x;
+                            let expected = bytes.iter().take_while(|y| y.$method()).count();
+                            let (head, tail) = bytes.$split();
+                            assert_eq!(head.len(), expected, "Failed on {}", x);
+                            assert_eq!(tail, &bytes[expected..], "Failed on {}", x);
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    against_take_while! {
+        ascii: split_while_ascii_2 == is_ascii_2,
+        alphabetic: split_while_ascii_alphabetic_2 == is_ascii_alphabetic_2,
+        alphanumeric: split_while_ascii_alphanumeric_2 == is_ascii_alphanumeric_2,
+        control: split_while_ascii_control_2 == is_ascii_control_2,
+        digit: split_while_ascii_digit_2 == is_ascii_digit_2,
+        graphic: split_while_ascii_graphic_2 == is_ascii_graphic_2,
+        hexdigit: split_while_ascii_hexdigit_2 == is_ascii_hexdigit_2,
+        lowercase: split_while_ascii_lowercase_2 == is_ascii_lowercase_2,
+        punctuation: split_while_ascii_punctuation_2 == is_ascii_punctuation_2,
+        uppercase: split_while_ascii_uppercase_2 == is_ascii_uppercase_2,
+        whitespace: split_while_ascii_whitespace_2 == is_ascii_whitespace_2,
+    }
+
+    #[test]
+    fn str() {
+        assert_eq!("".split_while_ascii_digit_2(), ("", ""));
+        assert_eq!("12345 + 6".split_while_ascii_digit_2(), ("12345", " + 6"));
+        assert_eq!(
+            "caf\u{e9}".split_while_ascii_alphabetic_2(),
+            ("caf", "\u{e9}")
+        );
+        assert_eq!("  \t\nx".split_while_ascii_whitespace_2(), ("  \t\n", "x"));
+        assert_eq!("\u{1F600}".split_while_ascii_2(), ("", "\u{1F600}"));
+    }
+
+    #[test]
+    fn custom_classes() {
+        use crate::http::HttpFieldValueByte;
+        use crate::ident::RustIdentContinue;
+
+        assert_eq!(
+            b"foo_bar2(x)".split_while_2(RustIdentContinue),
+            (&b"foo_bar2"[..], &b"(x)"[..])
+        );
+        assert_eq!(
+            "caf\u{e9} au lait\r\n".split_while_2(HttpFieldValueByte),
+            ("caf\u{e9} au lait", "\r\n")
+        );
+    }
+
+    // A class with the first byte of `'é'` (`C3 A9`) but not the second.
+    struct LeadByte;
+
+    impl AsciiClass for LeadByte {
+        fn contains(&self, x: u8) -> bool {
+            x.is_ascii_lowercase_2() || x == 0xC3
+        }
+    }
+
+    #[test]
+    fn str_backs_up_to_char_boundary() {
+        assert_eq!(
+            "cafe\u{e9}".as_bytes().split_while_2(LeadByte).0,
+            b"cafe\xC3"
+        );
+        assert_eq!("cafe\u{e9}".split_while_2(LeadByte), ("cafe", "\u{e9}"));
+        assert_eq!("\u{e9}".split_while_2(LeadByte), ("", "\u{e9}"));
+    }
+}
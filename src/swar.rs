@@ -0,0 +1,196 @@
+// Word-at-a-time ("SIMD within a register") helpers. A `u64` is treated as eight
+// bytes, loaded little-endian so that the first byte of the slice is the lowest byte
+// of the word. The predicates return a mask with the high bit of each byte set iff
+// the predicate holds for that byte, and all other bits clear.
+//
+// None of the predicates let a carry or borrow cross from one byte into the next,
+// so unlike the usual "does this word contain a zero byte" tricks, every byte of the
+// result is exact.
+
+pub(crate) const LOW_BITS: u64 = 0x0101_0101_0101_0101;
+pub(crate) const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// Repeats `x` in every byte of the word.
+#[inline]
+pub(crate) const fn splat(x: u8) -> u64 {
+    LOW_BITS * x as u64
+}
+
+/// Loads the first eight bytes of `bytes`.
+#[inline]
+pub(crate) fn load(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// Subtracts `x` from every byte, wrapping, like `u8::wrapping_sub`.
+#[inline]
+pub(crate) const fn wrapping_sub(word: u64, x: u8) -> u64 {
+    // Setting the high bit of every byte of `word` and clearing it in the subtrahend
+    // guarantees that no byte borrows from its neighbour. The high bits are then
+    // fixed up to what a real subtraction would have produced.
+    let y = splat(x);
+    ((word | HIGH_BITS) - (y & !HIGH_BITS)) ^ ((word ^ !y) & HIGH_BITS)
+}
+
+/// Bytes less than `n`, which must be at most 128.
+#[inline]
+pub(crate) const fn lt(word: u64, n: u8) -> u64 {
+    debug_assert!(n <= 128);
+    // The low seven bits of each byte plus `128 - n` fit in a byte, and the sum has
+    // its high bit set iff those bits are at least `n`. Bytes with their own high
+    // bit set are never less than `n`.
+    !(((word & !HIGH_BITS) + splat(128 - n)) | word) & HIGH_BITS
+}
+
+/// Bytes in `start..start + len`, where `len` is at most 128. This is the
+/// `x.wrapping_sub(start) < len` range trick of `is_ascii_graphic_2` and friends.
+#[inline]
+pub(crate) const fn in_range(word: u64, start: u8, len: u8) -> u64 {
+    lt(wrapping_sub(word, start), len)
+}
+
+/// Bytes equal to `x`.
+#[inline]
+pub(crate) const fn eq(word: u64, x: u8) -> u64 {
+    lt(word ^ splat(x), 1)
+}
+
+/// Packs the high bit of each byte of a `word_matches` mask into the low eight bits,
+/// with the first byte in the lowest bit.
+#[inline]
+pub(crate) const fn pack(matches: u64) -> u64 {
+    // After the shift, each byte is 0 or 1. The multiplication adds a copy of the
+    // word shifted left by `56 - 7 * i` for each `i` in `0..8`, which moves bit 0 of
+    // byte `i` to bit `56 + i`, and no two of those copies put a 1 in the same bit.
+    ((matches >> 7).wrapping_mul(0x0102_0408_1020_4080)) >> 56
+}
+
+/// Returns the index of the first byte of `bytes` that `is_match` holds for.
+/// `word_matches` must give the same answer as `is_match` for each byte of a word,
+/// in the mask format described at the top of this module.
+#[inline]
+pub(crate) fn position(
+    bytes: &[u8],
+    word_matches: impl Fn(u64) -> u64,
+    is_match: impl Fn(u8) -> bool,
+) -> Option<usize> {
+    let mut i = 0;
+    while let Some(word) = bytes.get(i..i + 8) {
+        let matches = word_matches(load(word));
+        if matches != 0 {
+            return Some(i + (matches.trailing_zeros() / 8) as usize);
+        }
+        i += 8;
+    }
+    bytes[i..].iter().position(|&x| is_match(x)).map(|j| i + j)
+}
+
+/// Returns the index of the last byte of `bytes` that `is_match` holds for. The
+/// arguments are the same as for `position`.
+#[inline]
+pub(crate) fn rposition(
+    bytes: &[u8],
+    word_matches: impl Fn(u64) -> u64,
+    is_match: impl Fn(u8) -> bool,
+) -> Option<usize> {
+    let mut chunks = bytes.rchunks_exact(8);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let matches = word_matches(load(chunk));
+        if matches != 0 {
+            // The last byte of the slice is the highest byte of the word.
+            let start = bytes.len() - (i + 1) * 8;
+            return Some(start + 7 - (matches.leading_zeros() / 8) as usize);
+        }
+    }
+    chunks.remainder().iter().rposition(|&x| is_match(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks `word_f` against `byte_f` for every byte value in every lane, with the
+    // other lanes filled with values that are likely to carry or borrow.
+    fn check(word_f: impl Fn(u64) -> u64, byte_f: impl Fn(u8) -> bool) {
+        for x in 0..=u8::MAX {
+            let fillers = [0x00, 0x01, 0x1F, 0x20, 0x7E, 0x7F, 0x80, 0x81, 0xFE, 0xFF];
+            for filler in fillers
+                .into_iter()
+                .chain([x.wrapping_sub(1), x.wrapping_add(1)])
+            {
+                for lane in 0..8 {
+                    let mut bytes = [filler; 8];
+                    bytes[lane] = x;
+                    let mask = word_f(u64::from_le_bytes(bytes));
+                    for (i, &byte) in bytes.iter().enumerate() {
+                        let expected = if byte_f(byte) { 0x80 } else { 0 };
+                        assert_eq!((mask >> (i * 8)) & 0xFF, expected, "Failed on {:?}", bytes);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lt_matches_u8() {
+        for n in [0, 1, 9, 32, 127, 128] {
+            check(|word| lt(word, n), |x| x < n);
+        }
+    }
+
+    #[test]
+    fn in_range_matches_u8() {
+        for (start, len) in [(b'!', 94), (b'0', 10), (0x80, 128), (0xF0, 32)] {
+            check(
+                |word| in_range(word, start, len),
+                |x| x.wrapping_sub(start) < len,
+            );
+        }
+    }
+
+    #[test]
+    fn eq_matches_u8() {
+        for n in [0, b'"', 0x7F, 0x80, 0xFF] {
+            check(|word| eq(word, n), |x| x == n);
+        }
+    }
+
+    #[test]
+    fn pack_every_byte() {
+        for bits in 0..=u8::MAX {
+            let matches = u64::from_le_bytes(core::array::from_fn(|i| {
+                if bits & (1 << i) != 0 {
+                    0x80
+                } else {
+                    0
+                }
+            }));
+            assert_eq!(pack(matches), bits as u64, "Failed on {}", bits);
+        }
+    }
+
+    #[test]
+    fn position_matches_iter() {
+        let bytes: [u8; 40] = core::array::from_fn(|i| i as u8);
+        for target in 0..=40 {
+            assert_eq!(
+                position(&bytes, |word| eq(word, target), |x| x == target),
+                bytes.iter().position(|&x| x == target)
+            );
+        }
+    }
+
+    #[test]
+    fn rposition_matches_iter() {
+        for len in 0..=40 {
+            let bytes: [u8; 40] = core::array::from_fn(|i| (i % 7) as u8);
+            let bytes = &bytes[..len];
+            for target in 0..=7 {
+                assert_eq!(
+                    rposition(bytes, |word| eq(word, target), |x| x == target),
+                    bytes.iter().rposition(|&x| x == target)
+                );
+            }
+        }
+    }
+}
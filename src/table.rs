@@ -0,0 +1,147 @@
+//! The `IsAscii2` classes as lookups in one 256-entry table, for targets where a
+//! load from L1 is cheaper than the branchless arithmetic, such as small in-order
+//! cores.
+//!
+//! Every class of a byte is in one `u16` entry, with the bits of `AsciiClasses`, so
+//! all of the classes share 512 bytes of cache.
+
+use crate::flags::AsciiClasses;
+
+/// The classes of every byte, indexed by the byte. The bits are those of
+/// `AsciiClasses`.
+pub static CLASSES: [u16; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let x = i as u8;
+        table[i] = (x.is_ascii() as u16)
+            | (x.is_ascii_alphabetic() as u16) << 1
+            | (x.is_ascii_alphanumeric() as u16) << 2
+            | (x.is_ascii_control() as u16) << 3
+            | (x.is_ascii_digit() as u16) << 4
+            | (x.is_ascii_graphic() as u16) << 5
+            | (x.is_ascii_hexdigit() as u16) << 6
+            | (x.is_ascii_lowercase() as u16) << 7
+            | (x.is_ascii_punctuation() as u16) << 8
+            | (x.is_ascii_uppercase() as u16) << 9
+            | (x.is_ascii_whitespace() as u16) << 10;
+        i += 1;
+    }
+    table
+};
+
+/// Returns a bitmap of the bytes in `class`, where bit `x` is 1 iff `x` is in it. All
+/// of the classes are ASCII.
+pub(crate) const fn ascii_bits(class: AsciiClasses) -> u128 {
+    let mut bits = 0;
+    let mut x = 0;
+    while x < 128 {
+        if CLASSES[x] & class.bits() != 0 {
+            bits |= 1 << x;
+        }
+        x += 1;
+    }
+    bits
+}
+
+/// Returns every class that `x` is in, with one load. This is `AsciiClasses::of`
+/// using the table.
+#[must_use]
+#[inline]
+pub fn classes(x: u8) -> AsciiClasses {
+    AsciiClasses::from_bits_truncate(CLASSES[x as usize])
+}
+
+// Defines the trait and its `u8` and `char` implementations, each testing one bit of
+// the table entry.
+macro_rules! table_methods {
+    ($($method: ident => $class: ident,)*) => {
+        pub trait IsAsciiTable {
+            $(
+                #[must_use]
+                fn $method(&self) -> bool;
+            )*
+        }
+
+        impl IsAsciiTable for u8 {
+            $(
+                #[inline]
+                fn $method(&self) -> bool {
+                    CLASSES[*self as usize] & AsciiClasses::$class.bits() != 0
+                }
+            )*
+        }
+
+        // `char`s above `'\u{FF}'` fail the conversion, and so are in no class.
+        impl IsAsciiTable for char {
+            $(
+                #[inline]
+                fn $method(&self) -> bool {
+                    u8::try_from(*self).is_ok_and(|x| x.$method())
+                }
+            )*
+        }
+    };
+}
+
+table_methods! {
+    is_ascii_table => ASCII,
+    is_ascii_alphabetic_table => ALPHABETIC,
+    is_ascii_alphanumeric_table => ALPHANUMERIC,
+    is_ascii_control_table => CONTROL,
+    is_ascii_digit_table => DIGIT,
+    is_ascii_graphic_table => GRAPHIC,
+    is_ascii_hexdigit_table => HEXDIGIT,
+    is_ascii_lowercase_table => LOWERCASE,
+    is_ascii_punctuation_table => PUNCTUATION,
+    is_ascii_uppercase_table => UPPERCASE,
+    is_ascii_whitespace_table => WHITESPACE,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IsAscii2;
+
+    macro_rules! against_core {
+        ($($test: ident: $core: ident => $table: ident,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    for x in 0..=u8::MAX {
+                        assert!(x.$table() == x.$core(), "Failed on {}", x);
+                    }
+                    for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+                        assert!(x.$table() == x.$core(), "Failed on {}", x);
+                    }
+                }
+            )*
+        };
+    }
+
+    against_core! {
+        ascii_table: is_ascii => is_ascii_table,
+        alphabetic_table: is_ascii_alphabetic => is_ascii_alphabetic_table,
+        alphanumeric_table: is_ascii_alphanumeric => is_ascii_alphanumeric_table,
+        control_table: is_ascii_control => is_ascii_control_table,
+        digit_table: is_ascii_digit => is_ascii_digit_table,
+        graphic_table: is_ascii_graphic => is_ascii_graphic_table,
+        hexdigit_table: is_ascii_hexdigit => is_ascii_hexdigit_table,
+        lowercase_table: is_ascii_lowercase => is_ascii_lowercase_table,
+        punctuation_table: is_ascii_punctuation => is_ascii_punctuation_table,
+        uppercase_table: is_ascii_uppercase => is_ascii_uppercase_table,
+        whitespace_table: is_ascii_whitespace => is_ascii_whitespace_table,
+    }
+
+    #[test]
+    fn classes_match_flags() {
+        for x in 0..=u8::MAX {
+            assert!(classes(x) == AsciiClasses::of(x), "Failed on {}", x);
+            assert!(
+                x.is_ascii_punctuation_table() == x.is_ascii_punctuation_2(),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+}
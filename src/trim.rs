@@ -0,0 +1,287 @@
+//! Trimming and splitting on the whitespace of `is_ascii_whitespace_2`, the same
+//! whitespace as `core`'s `trim_ascii` and `split_ascii_whitespace`, with the
+//! boundaries found a word at a time.
+
+use core::iter::FusedIterator;
+use core::str;
+
+use crate::class::words;
+use crate::swar;
+use crate::IsAscii2;
+
+#[inline]
+pub(crate) fn find_whitespace(bytes: &[u8]) -> Option<usize> {
+    swar::position(bytes, words::whitespace, |x| x.is_ascii_whitespace_2())
+}
+
+#[inline]
+pub(crate) fn find_non_whitespace(bytes: &[u8]) -> Option<usize> {
+    swar::position(
+        bytes,
+        |word| !words::whitespace(word) & swar::HIGH_BITS,
+        |x| !x.is_ascii_whitespace_2(),
+    )
+}
+
+#[inline]
+fn rfind_whitespace(bytes: &[u8]) -> Option<usize> {
+    swar::rposition(bytes, words::whitespace, |x| x.is_ascii_whitespace_2())
+}
+
+#[inline]
+fn rfind_non_whitespace(bytes: &[u8]) -> Option<usize> {
+    swar::rposition(
+        bytes,
+        |word| !words::whitespace(word) & swar::HIGH_BITS,
+        |x| !x.is_ascii_whitespace_2(),
+    )
+}
+
+// Pieces are usually separated by a single whitespace byte, which is quicker to skip
+// one byte at a time than with a word.
+#[inline]
+fn skip_separator(bytes: &[u8]) -> &[u8] {
+    match bytes {
+        [x, rest @ ..] if x.is_ascii_whitespace_2() => match rest {
+            [y, ..] if !y.is_ascii_whitespace_2() => rest,
+            _ => rest.trim_ascii_whitespace_start_2(),
+        },
+        _ => bytes,
+    }
+}
+
+#[inline]
+fn rskip_separator(bytes: &[u8]) -> &[u8] {
+    match bytes {
+        [rest @ .., x] if x.is_ascii_whitespace_2() => match rest {
+            [.., y] if !y.is_ascii_whitespace_2() => rest,
+            _ => rest.trim_ascii_whitespace_end_2(),
+        },
+        _ => bytes,
+    }
+}
+
+pub trait TrimAsciiWhitespace2 {
+    /// Returns `self` without leading or trailing ASCII whitespace.
+    fn trim_ascii_whitespace_2(&self) -> &Self;
+    /// Returns `self` without leading ASCII whitespace.
+    fn trim_ascii_whitespace_start_2(&self) -> &Self;
+    /// Returns `self` without trailing ASCII whitespace.
+    fn trim_ascii_whitespace_end_2(&self) -> &Self;
+    /// Returns an iterator over the non-empty pieces of `self` between runs of ASCII
+    /// whitespace.
+    fn split_ascii_whitespace_2(&self) -> SplitAsciiWhitespace2<'_, Self>;
+}
+
+impl TrimAsciiWhitespace2 for [u8] {
+    #[inline]
+    fn trim_ascii_whitespace_2(&self) -> &Self {
+        self.trim_ascii_whitespace_start_2()
+            .trim_ascii_whitespace_end_2()
+    }
+
+    #[inline]
+    fn trim_ascii_whitespace_start_2(&self) -> &Self {
+        match find_non_whitespace(self) {
+            Some(start) => &self[start..],
+            None => &[],
+        }
+    }
+
+    #[inline]
+    fn trim_ascii_whitespace_end_2(&self) -> &Self {
+        match rfind_non_whitespace(self) {
+            Some(end) => &self[..end + 1],
+            None => &[],
+        }
+    }
+
+    #[inline]
+    fn split_ascii_whitespace_2(&self) -> SplitAsciiWhitespace2<'_, Self> {
+        SplitAsciiWhitespace2 { rest: self }
+    }
+}
+
+// ASCII whitespace is only ever a whole `char`, so every boundary found below is a
+// `char` boundary.
+impl TrimAsciiWhitespace2 for str {
+    #[inline]
+    fn trim_ascii_whitespace_2(&self) -> &Self {
+        unsafe { str::from_utf8_unchecked(self.as_bytes().trim_ascii_whitespace_2()) }
+    }
+
+    #[inline]
+    fn trim_ascii_whitespace_start_2(&self) -> &Self {
+        unsafe { str::from_utf8_unchecked(self.as_bytes().trim_ascii_whitespace_start_2()) }
+    }
+
+    #[inline]
+    fn trim_ascii_whitespace_end_2(&self) -> &Self {
+        unsafe { str::from_utf8_unchecked(self.as_bytes().trim_ascii_whitespace_end_2()) }
+    }
+
+    #[inline]
+    fn split_ascii_whitespace_2(&self) -> SplitAsciiWhitespace2<'_, Self> {
+        SplitAsciiWhitespace2 { rest: self }
+    }
+}
+
+/// An iterator over the pieces of a `[u8]` or `str` between runs of ASCII
+/// whitespace. See `TrimAsciiWhitespace2::split_ascii_whitespace_2`.
+#[derive(Debug)]
+pub struct SplitAsciiWhitespace2<'a, T: ?Sized> {
+    // Neither end of `rest` is trimmed until it's needed.
+    rest: &'a T,
+}
+
+// Not derived, since that would require `T: Clone`.
+impl<T: ?Sized> Clone for SplitAsciiWhitespace2<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SplitAsciiWhitespace2 { rest: self.rest }
+    }
+}
+
+impl<'a> Iterator for SplitAsciiWhitespace2<'a, [u8]> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = skip_separator(self.rest);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        let end = find_whitespace(rest).unwrap_or(rest.len());
+        let (piece, rest) = rest.split_at(end);
+        self.rest = rest;
+        Some(piece)
+    }
+}
+
+impl DoubleEndedIterator for SplitAsciiWhitespace2<'_, [u8]> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let rest = rskip_separator(self.rest);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        let start = rfind_whitespace(rest).map_or(0, |i| i + 1);
+        let (rest, piece) = rest.split_at(start);
+        self.rest = rest;
+        Some(piece)
+    }
+}
+
+impl FusedIterator for SplitAsciiWhitespace2<'_, [u8]> {}
+
+impl<'a> Iterator for SplitAsciiWhitespace2<'a, str> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = SplitAsciiWhitespace2 {
+            rest: self.rest.as_bytes(),
+        };
+        let piece = bytes.next();
+        // SAFETY: See the comment on the `str` impl of `TrimAsciiWhitespace2`.
+        unsafe {
+            self.rest = str::from_utf8_unchecked(bytes.rest);
+            piece.map(|piece| str::from_utf8_unchecked(piece))
+        }
+    }
+}
+
+impl DoubleEndedIterator for SplitAsciiWhitespace2<'_, str> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut bytes = SplitAsciiWhitespace2 {
+            rest: self.rest.as_bytes(),
+        };
+        let piece = bytes.next_back();
+        // SAFETY: See the comment on the `str` impl of `TrimAsciiWhitespace2`.
+        unsafe {
+            self.rest = str::from_utf8_unchecked(bytes.rest);
+            piece.map(|piece| str::from_utf8_unchecked(piece))
+        }
+    }
+}
+
+impl FusedIterator for SplitAsciiWhitespace2<'_, str> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Mostly whitespace and letters, with some bytes that are near whitespace.
+    const ALPHABET: &[u8] = b" \t\n\x0B\x0C\r\x1F!abc\x80\xFF";
+
+    fn random_bytes(rng: &mut StdRng) -> ([u8; 40], usize) {
+        let bytes = core::array::from_fn(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())]);
+        (bytes, rng.gen_range(0..=40))
+    }
+
+    #[test]
+    fn trim_matches_core() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10000 {
+            let (bytes, len) = random_bytes(&mut rng);
+            let bytes = &bytes[..len];
+            assert_eq!(bytes.trim_ascii_whitespace_2(), bytes.trim_ascii());
+            assert_eq!(
+                bytes.trim_ascii_whitespace_start_2(),
+                bytes.trim_ascii_start()
+            );
+            assert_eq!(bytes.trim_ascii_whitespace_end_2(), bytes.trim_ascii_end());
+        }
+    }
+
+    #[test]
+    fn split_matches_core() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10000 {
+            let (bytes, len) = random_bytes(&mut rng);
+            let bytes = &bytes[..len];
+            let expected = bytes
+                .split(|x| x.is_ascii_whitespace())
+                .filter(|piece| !piece.is_empty());
+            assert!(bytes.split_ascii_whitespace_2().eq(expected.clone()));
+            assert!(bytes
+                .split_ascii_whitespace_2()
+                .rev()
+                .eq(expected.clone().rev()));
+
+            // Alternating ends meet in the middle without losing or repeating a piece.
+            let mut split = bytes.split_ascii_whitespace_2();
+            let mut front = 0;
+            let mut back = 0;
+            while let Some(piece) = if (front + back) % 2 == 0 {
+                split.next().inspect(|_| front += 1)
+            } else {
+                split.next_back().inspect(|_| back += 1)
+            } {
+                assert!(!piece.is_empty());
+            }
+            assert_eq!(front + back, expected.count());
+        }
+    }
+
+    #[test]
+    fn str() {
+        let s = "\u{A0} caf\u{e9}\tau\n\x0B lait \r\n";
+        assert_eq!(s.trim_ascii_whitespace_2(), s.trim_ascii());
+        assert_eq!(s.trim_ascii_whitespace_start_2(), s.trim_ascii_start());
+        assert_eq!(s.trim_ascii_whitespace_end_2(), s.trim_ascii_end());
+        assert!(s.split_ascii_whitespace_2().eq(s.split_ascii_whitespace()));
+        assert!(s
+            .split_ascii_whitespace_2()
+            .rev()
+            .eq(s.split_ascii_whitespace().rev()));
+        assert_eq!("".trim_ascii_whitespace_2(), "");
+        assert_eq!(" \t ".trim_ascii_whitespace_2(), "");
+        assert_eq!(" \t ".split_ascii_whitespace_2().next(), None);
+    }
+}
@@ -0,0 +1,453 @@
+//! Picking the fastest slice scan for each `IsAscii2` class on the running CPU, by
+//! timing the backends the first time a class is used.
+//!
+//! The backends are the word-at-a-time `swar` scan of the `class` types, 16 lanes of
+//! `simd`, which is SSE2 on x86-64, the `pshufb` lookups of `shuffle`, which use AVX2
+//! or SSSE3 when the CPU has them, and the 256-entry `table`. Which one wins depends
+//! on the input length as well as the CPU, so each class gets a choice for short,
+//! medium and long inputs, and the three are packed into one `AtomicU8` per class.
+//!
+//! Timing needs the `std` feature. Without it, or on targets with no clock, every
+//! class gets `Decision::FALLBACK`, so the choice never depends on the machine.
+
+use core::fmt;
+use core::simd::prelude::*;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::class::*;
+use crate::flags::AsciiClasses;
+use crate::shuffle::NibbleClass;
+use crate::simd;
+use crate::table::{self, CLASSES};
+
+/// Inputs shorter than this are short.
+pub const SHORT_LEN: usize = 64;
+/// Inputs at least this long are long, and the rest are medium.
+pub const LONG_LEN: usize = 1024;
+
+/// A way of finding the first nonmember of a class in a slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// `AsciiClass::find_nonmember` of the `class` type, eight bytes at a time.
+    Swar,
+    /// `simd::find_nonmember` with 16 lanes.
+    Simd,
+    /// `NibbleClass::find_nonmember`.
+    Shuffle,
+    /// One `table::CLASSES` lookup per byte.
+    Table,
+}
+
+impl Backend {
+    const ALL: [Backend; 4] = [
+        Backend::Swar,
+        Backend::Simd,
+        Backend::Shuffle,
+        Backend::Table,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Backend::Swar => "swar",
+            Backend::Simd => "simd",
+            Backend::Shuffle => "shuffle",
+            Backend::Table => "table",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The backends chosen for a class, by input length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Decision {
+    /// For inputs shorter than `SHORT_LEN`.
+    pub short: Backend,
+    /// For inputs from `SHORT_LEN` up to `LONG_LEN`.
+    pub medium: Backend,
+    /// For inputs of `LONG_LEN` or more.
+    pub long: Backend,
+    /// Whether the backends were timed, rather than being the fallback.
+    pub measured: bool,
+}
+
+// The state of a class that hasn't been tuned is 0, so a tuned one has this bit set.
+const TUNED: u8 = 1 << 7;
+const MEASURED: u8 = 1 << 6;
+
+impl Decision {
+    /// The choices when there's no timing: SWAR for inputs that fill few vectors, and
+    /// the portable `simd` backend, which every target has, for the rest.
+    pub const FALLBACK: Decision = Decision {
+        short: Backend::Swar,
+        medium: Backend::Simd,
+        long: Backend::Simd,
+        measured: false,
+    };
+
+    /// Returns the backend for an input of `len` bytes.
+    #[must_use]
+    #[inline]
+    pub const fn backend(self, len: usize) -> Backend {
+        if len < SHORT_LEN {
+            self.short
+        } else if len < LONG_LEN {
+            self.medium
+        } else {
+            self.long
+        }
+    }
+
+    // Two bits per backend, from the bottom, then `MEASURED` and `TUNED`.
+    const fn pack(self) -> u8 {
+        TUNED
+            | if self.measured { MEASURED } else { 0 }
+            | self.short as u8
+            | (self.medium as u8) << 2
+            | (self.long as u8) << 4
+    }
+
+    const fn unpack(state: u8) -> Option<Self> {
+        if state & TUNED == 0 {
+            return None;
+        }
+        Some(Decision {
+            short: Backend::ALL[(state & 3) as usize],
+            medium: Backend::ALL[(state >> 2 & 3) as usize],
+            long: Backend::ALL[(state >> 4 & 3) as usize],
+            measured: state & MEASURED != 0,
+        })
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "short: {}, medium: {}, long: {} ({})",
+            self.short,
+            self.medium,
+            self.long,
+            if self.measured {
+                "measured"
+            } else {
+                "fallback"
+            }
+        )
+    }
+}
+
+mod sealed {
+    use super::*;
+
+    // What the backends need to know about a class.
+    pub trait Sealed {
+        const INDEX: usize;
+        const FLAG: AsciiClasses;
+        const NIBBLES: NibbleClass = NibbleClass::new([table::ascii_bits(Self::FLAG), 0]);
+
+        fn simd_matches(x: Simd<u8, 16>) -> Mask<i8, 16>;
+    }
+}
+
+/// An `IsAscii2` class of the `class` module, which can be tuned.
+pub trait Tunable: AsciiClass + Copy + Default + sealed::Sealed {
+    /// The name of the class, for logging.
+    const NAME: &'static str;
+}
+
+/// The state of each class, in `Decision::pack` form.
+static STATES: [AtomicU8; 11] = [const { AtomicU8::new(0) }; 11];
+
+/// Returns the index of the first byte of `bytes` that is not in `C`, or `None` if
+/// every byte is, with the backend tuned for `C` and the length of `bytes`. The first
+/// call for each class tunes it.
+#[inline]
+pub fn find_nonmember<C: Tunable>(bytes: &[u8]) -> Option<usize> {
+    run::<C>(decision::<C>().backend(bytes.len()), bytes)
+}
+
+/// Returns the decision for `C`, tuning it first if it hasn't been.
+#[inline]
+pub fn decision<C: Tunable>() -> Decision {
+    match cached_decision::<C>() {
+        Some(decision) => decision,
+        None => tune::<C>(),
+    }
+}
+
+/// Returns the decision for `C` if it has been made, without tuning.
+#[must_use]
+#[inline]
+pub fn cached_decision<C: Tunable>() -> Option<Decision> {
+    Decision::unpack(STATES[C::INDEX].load(Ordering::Relaxed))
+}
+
+#[inline]
+fn run<C: Tunable>(backend: Backend, bytes: &[u8]) -> Option<usize> {
+    match backend {
+        Backend::Swar => C::default().find_nonmember(bytes),
+        Backend::Simd => simd::find_nonmember(bytes, C::simd_matches),
+        Backend::Shuffle => C::NIBBLES.find_nonmember(bytes),
+        Backend::Table => bytes
+            .iter()
+            .position(|&x| CLASSES[x as usize] & C::FLAG.bits() == 0),
+    }
+}
+
+// Threads that race to tune the same class each store their own decision, and the
+// last store wins. Every decision they could make is correct, just not equally fast.
+#[cold]
+fn tune<C: Tunable>() -> Decision {
+    let decision = measure::<C>().unwrap_or(Decision::FALLBACK);
+    STATES[C::INDEX].store(decision.pack(), Ordering::Relaxed);
+    decision
+}
+
+/// Times every backend on inputs of `C`'s members, returning `None` if there's no
+/// clock, or if it's too coarse to tell the backends apart.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+fn measure<C: Tunable>() -> Option<Decision> {
+    use core::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    // All members, so that every backend scans the whole input.
+    let mut buffer = [0; 4 * LONG_LEN];
+    let mut members = (0..128).filter(|&x| C::default().contains(x)).cycle();
+    buffer.fill_with(|| members.next().unwrap());
+
+    let pick = |len: usize| {
+        let bytes = &buffer[..len];
+        // About the same number of bytes at every length.
+        let rounds = 16 * buffer.len() / len;
+        let mut best: Option<(Duration, Backend)> = None;
+        for backend in Backend::ALL {
+            // The fastest of a few tries, which skips most interrupts and clock
+            // ramp-up.
+            let time = (0..3)
+                .map(|_| {
+                    let start = Instant::now();
+                    for _ in 0..rounds {
+                        black_box(run::<C>(backend, black_box(bytes)));
+                    }
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+            if best.is_none_or(|(best_time, _)| time < best_time) {
+                best = Some((time, backend));
+            }
+        }
+        best.filter(|(time, _)| !time.is_zero())
+            .map(|(_, backend)| backend)
+    };
+
+    Some(Decision {
+        short: pick(SHORT_LEN / 2)?,
+        medium: pick(LONG_LEN / 4)?,
+        long: pick(4 * LONG_LEN)?,
+        measured: true,
+    })
+}
+
+#[cfg(not(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+)))]
+#[allow(clippy::extra_unused_type_parameters)] // To match the timed version.
+fn measure<C: Tunable>() -> Option<Decision> {
+    None
+}
+
+/// A class whose `find_nonmember`, and so `contains_all`, uses the tuned backend.
+///
+/// ```
+/// use is_ascii_speedups::class::AsciiAlphanumeric;
+/// use is_ascii_speedups::tune::Autotuned;
+/// use is_ascii_speedups::AsciiClass;
+///
+/// assert_eq!(Autotuned(AsciiAlphanumeric).find_nonmember(b"user_name"), Some(4));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Autotuned<C>(pub C);
+
+impl<C: Tunable> AsciiClass for Autotuned<C> {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        self.0.contains(x)
+    }
+
+    #[inline]
+    fn contains_char(&self, x: char) -> bool {
+        self.0.contains_char(x)
+    }
+
+    #[inline]
+    fn word_matches(&self, word: u64) -> u64 {
+        self.0.word_matches(word)
+    }
+
+    #[inline]
+    fn find_nonmember(&self, bytes: &[u8]) -> Option<usize> {
+        find_nonmember::<C>(bytes)
+    }
+}
+
+macro_rules! tunable {
+    ($($index: literal: $class: ident => $flag: ident, $simd: ident,)*) => {
+        $(
+            impl sealed::Sealed for $class {
+                const INDEX: usize = $index;
+                const FLAG: AsciiClasses = AsciiClasses::$flag;
+
+                #[inline]
+                fn simd_matches(x: Simd<u8, 16>) -> Mask<i8, 16> {
+                    simd::$simd(x)
+                }
+            }
+
+            impl Tunable for $class {
+                const NAME: &'static str = stringify!($class);
+            }
+        )*
+
+        /// Tunes every class now, rather than at first use, replacing any earlier
+        /// decisions.
+        pub fn autotune() {
+            $(tune::<$class>();)*
+        }
+
+        /// Returns the name of every class and its decision, if it has been made,
+        /// for logging.
+        #[must_use]
+        pub fn decisions() -> [(&'static str, Option<Decision>); 11] {
+            [$(($class::NAME, cached_decision::<$class>()),)*]
+        }
+
+        #[cfg(test)]
+        macro_rules! for_every_class {
+            ($f: ident) => {
+                $($f::<$class>();)*
+            };
+        }
+    };
+}
+
+tunable! {
+    0: Ascii => ASCII, is_ascii_simd,
+    1: AsciiAlphabetic => ALPHABETIC, is_ascii_alphabetic_simd,
+    2: AsciiAlphanumeric => ALPHANUMERIC, is_ascii_alphanumeric_simd,
+    3: AsciiControl => CONTROL, is_ascii_control_simd,
+    4: AsciiDigit => DIGIT, is_ascii_digit_simd,
+    5: AsciiGraphic => GRAPHIC, is_ascii_graphic_simd,
+    6: AsciiHexdigit => HEXDIGIT, is_ascii_hexdigit_simd,
+    7: AsciiLowercase => LOWERCASE, is_ascii_lowercase_simd,
+    8: AsciiPunctuation => PUNCTUATION, is_ascii_punctuation_simd,
+    9: AsciiUppercase => UPPERCASE, is_ascii_uppercase_simd,
+    10: AsciiWhitespace => WHITESPACE, is_ascii_whitespace_simd,
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn backends_agree<C: Tunable>() {
+        let class = C::default();
+        let mut rng = StdRng::seed_from_u64(C::INDEX as u64);
+        for _ in 0..200 {
+            // Long enough for every length class, and mostly members, so that the
+            // first nonmember is often past the first vector.
+            let len =
+                [rng.gen_range(0..SHORT_LEN), rng.gen_range(0..2 * LONG_LEN)][rng.gen_range(0..2)];
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| loop {
+                    let x = rng.gen();
+                    if class.contains(x) || rng.gen_ratio(1, 500) {
+                        break x;
+                    }
+                })
+                .collect();
+            let expected = bytes.iter().position(|&x| !class.contains(x));
+            for backend in Backend::ALL {
+                assert_eq!(
+                    run::<C>(backend, &bytes),
+                    expected,
+                    "Failed on {} with {}",
+                    C::NAME,
+                    backend
+                );
+            }
+            assert_eq!(Autotuned(class).find_nonmember(&bytes), expected);
+        }
+    }
+
+    #[test]
+    fn every_backend_matches_contains() {
+        for_every_class!(backends_agree);
+    }
+
+    #[test]
+    fn decisions_pack() {
+        assert_eq!(Decision::unpack(0), None);
+        for short in Backend::ALL {
+            for medium in Backend::ALL {
+                for long in Backend::ALL {
+                    for measured in [false, true] {
+                        let decision = Decision {
+                            short,
+                            medium,
+                            long,
+                            measured,
+                        };
+                        assert_eq!(Decision::unpack(decision.pack()), Some(decision));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn autotune_decides_every_class() {
+        autotune();
+        for (name, decision) in decisions() {
+            let decision = decision.unwrap();
+            // Linux always has a fine enough clock.
+            assert!(
+                decision.measured == cfg!(feature = "std"),
+                "Failed on {}",
+                name
+            );
+            if !decision.measured {
+                assert_eq!(decision, Decision::FALLBACK, "Failed on {}", name);
+            }
+        }
+        assert_eq!(
+            cached_decision::<AsciiDigit>(),
+            Some(decision::<AsciiDigit>())
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Decision::FALLBACK.to_string(),
+            "short: swar, medium: simd, long: simd (fallback)"
+        );
+        assert_eq!(Decision::FALLBACK.backend(SHORT_LEN - 1), Backend::Swar);
+        assert_eq!(Decision::FALLBACK.backend(LONG_LEN), Backend::Simd);
+    }
+}
@@ -0,0 +1,467 @@
+//! Character classes from the RFC 3986 URI grammar, and percent-encoding.
+
+use core::mem;
+
+use crate::class::{ascii_bitmap, in_ascii_bitmap, AsciiClass};
+use crate::codec::{DecodeError, EncodeError};
+use crate::IsAscii2;
+
+// `unreserved = ALPHA / DIGIT / "-" / "." / "_" / "~"`
+const UNRESERVED_BITMAP: u128 = ascii_bitmap(&[
+    (b'-', b'.'),
+    (b'0', b'9'),
+    (b'A', b'Z'),
+    (b'_', b'_'),
+    (b'a', b'z'),
+    (b'~', b'~'),
+]);
+
+// `sub-delims = "!" / "$" / "&" / "'" / "(" / ")" / "*" / "+" / "," / ";" / "="`
+const SUB_DELIM_BITMAP: u128 = ascii_bitmap(&[
+    (b'!', b'!'),
+    (b'$', b'$'),
+    (b'&', b','),
+    (b';', b';'),
+    (b'=', b'='),
+]);
+
+// `gen-delims = ":" / "/" / "?" / "#" / "[" / "]" / "@"`
+const GEN_DELIM_BITMAP: u128 = ascii_bitmap(&[
+    (b'#', b'#'),
+    (b'/', b'/'),
+    (b':', b':'),
+    (b'?', b'@'),
+    (b'[', b'['),
+    (b']', b']'),
+]);
+
+// `pchar = unreserved / pct-encoded / sub-delims / ":" / "@"`, without the
+// three-byte `pct-encoded` alternative.
+const PCHAR_BITMAP: u128 =
+    UNRESERVED_BITMAP | SUB_DELIM_BITMAP | ascii_bitmap(&[(b':', b':'), (b'@', b'@')]);
+
+pub trait IsUri2 {
+    fn is_uri_unreserved_2(&self) -> bool;
+    fn is_uri_sub_delim_2(&self) -> bool;
+    fn is_uri_gen_delim_2(&self) -> bool;
+    /// `%` is not a `pchar` on its own. For slices, this only accepts literal
+    /// `pchar`s, so percent-encoded data should be checked with
+    /// `percent_decode` instead.
+    fn is_uri_pchar_2(&self) -> bool;
+}
+
+impl IsUri2 for u8 {
+    #[inline]
+    fn is_uri_unreserved_2(&self) -> bool {
+        in_ascii_bitmap(UNRESERVED_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_sub_delim_2(&self) -> bool {
+        in_ascii_bitmap(SUB_DELIM_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_gen_delim_2(&self) -> bool {
+        in_ascii_bitmap(GEN_DELIM_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_pchar_2(&self) -> bool {
+        in_ascii_bitmap(PCHAR_BITMAP, *self as u32)
+    }
+}
+
+impl IsUri2 for char {
+    #[inline]
+    fn is_uri_unreserved_2(&self) -> bool {
+        in_ascii_bitmap(UNRESERVED_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_sub_delim_2(&self) -> bool {
+        in_ascii_bitmap(SUB_DELIM_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_gen_delim_2(&self) -> bool {
+        in_ascii_bitmap(GEN_DELIM_BITMAP, *self as u32)
+    }
+
+    #[inline]
+    fn is_uri_pchar_2(&self) -> bool {
+        in_ascii_bitmap(PCHAR_BITMAP, *self as u32)
+    }
+}
+
+impl IsUri2 for [u8] {
+    #[inline]
+    fn is_uri_unreserved_2(&self) -> bool {
+        UriUnreserved.contains_all(self)
+    }
+
+    #[inline]
+    fn is_uri_sub_delim_2(&self) -> bool {
+        UriSubDelim.contains_all(self)
+    }
+
+    #[inline]
+    fn is_uri_gen_delim_2(&self) -> bool {
+        UriGenDelim.contains_all(self)
+    }
+
+    #[inline]
+    fn is_uri_pchar_2(&self) -> bool {
+        UriPchar.contains_all(self)
+    }
+}
+
+impl IsUri2 for str {
+    #[inline]
+    fn is_uri_unreserved_2(&self) -> bool {
+        self.as_bytes().is_uri_unreserved_2()
+    }
+
+    #[inline]
+    fn is_uri_sub_delim_2(&self) -> bool {
+        self.as_bytes().is_uri_sub_delim_2()
+    }
+
+    #[inline]
+    fn is_uri_gen_delim_2(&self) -> bool {
+        self.as_bytes().is_uri_gen_delim_2()
+    }
+
+    #[inline]
+    fn is_uri_pchar_2(&self) -> bool {
+        self.as_bytes().is_uri_pchar_2()
+    }
+}
+
+/// The `unreserved` class. This is the usual safe set for `percent_encode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UriUnreserved;
+
+/// The `sub-delims` class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UriSubDelim;
+
+/// The `gen-delims` class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UriGenDelim;
+
+/// The single-byte alternatives of the `pchar` class. This is the safe set for
+/// encoding a path segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UriPchar;
+
+impl AsciiClass for UriUnreserved {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_uri_unreserved_2()
+    }
+}
+
+impl AsciiClass for UriSubDelim {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_uri_sub_delim_2()
+    }
+}
+
+impl AsciiClass for UriGenDelim {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_uri_gen_delim_2()
+    }
+}
+
+impl AsciiClass for UriPchar {
+    #[inline]
+    fn contains(&self, x: u8) -> bool {
+        x.is_uri_pchar_2()
+    }
+}
+
+const UPPERCASE_HEXDIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Returns the length of the percent-encoding of `input` when the bytes in `safe`
+/// are left as they are.
+#[must_use]
+pub fn percent_encoded_len<C: AsciiClass>(input: &[u8], safe: &C) -> usize {
+    input.len() + 2 * input.iter().filter(|&&x| !safe.contains(x)).count()
+}
+
+/// Percent-encodes `input` into the start of `output` and returns the number of
+/// bytes written. Bytes in `safe` are copied and all others are written as `%XX`
+/// with uppercase hexadecimal digits, as RFC 3986, section 2.1, recommends.
+///
+/// `%` itself must not be in `safe`, or the output cannot be decoded.
+pub fn percent_encode<C: AsciiClass>(
+    input: &[u8],
+    output: &mut [u8],
+    safe: &C,
+) -> Result<usize, EncodeError> {
+    let required = percent_encoded_len(input, safe);
+    let mut output = match output.get_mut(..required) {
+        Some(output) => output,
+        None => return Err(EncodeError::OutputTooSmall { required }),
+    };
+
+    let mut input = input;
+    while let Some(len) = safe.find_nonmember(input) {
+        let (run, out) = mem::take(&mut output).split_at_mut(len);
+        run.copy_from_slice(&input[..len]);
+        let x = input[len];
+        out[..3].copy_from_slice(&[
+            b'%',
+            UPPERCASE_HEXDIGITS[(x >> 4) as usize],
+            UPPERCASE_HEXDIGITS[(x & 0b1111) as usize],
+        ]);
+        input = &input[len + 1..];
+        output = &mut out[3..];
+    }
+    output.copy_from_slice(input);
+
+    Ok(required)
+}
+
+// Converts an ASCII hexadecimal digit to its value. Any other byte gives garbage.
+#[inline]
+fn hexdigit_value(x: u8) -> u8 {
+    // Decimal digits are `0x30` to `0x39`. Letters are `0x41` to `0x46` or `0x61`
+    // to `0x66`, and `& 0b1111` leaves 1 to 6 for both cases.
+    if x <= b'9' {
+        x.wrapping_sub(b'0')
+    } else {
+        (x & 0b1111) + 9
+    }
+}
+
+/// Returns the length of the percent-decoding of `input`, after checking that
+/// every `%` is followed by two hexadecimal digits.
+pub fn percent_decoded_len(input: &[u8]) -> Result<usize, DecodeError> {
+    let mut len = input.len();
+    let mut i = 0;
+    while let Some(offset) = input[i..].iter().position(|&x| x == b'%') {
+        i += offset;
+        for index in [i + 1, i + 2] {
+            match input.get(index) {
+                Some(x) if x.is_ascii_hexdigit_2() => {}
+                Some(&byte) => return Err(DecodeError::InvalidByte { index, byte }),
+                None => return Err(DecodeError::InvalidLength),
+            }
+        }
+        len -= 2;
+        i += 3;
+    }
+    Ok(len)
+}
+
+/// Percent-decodes `input` into the start of `output` and returns the number of
+/// bytes written. Both uppercase and lowercase hexadecimal digits are accepted.
+pub fn percent_decode(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    let required = percent_decoded_len(input)?;
+    let mut output = match output.get_mut(..required) {
+        Some(output) => output,
+        None => return Err(DecodeError::OutputTooSmall { required }),
+    };
+
+    let mut input = input;
+    while let Some(len) = input.iter().position(|&x| x == b'%') {
+        let (run, out) = mem::take(&mut output).split_at_mut(len);
+        run.copy_from_slice(&input[..len]);
+        out[0] = (hexdigit_value(input[len + 1]) << 4) | hexdigit_value(input[len + 2]);
+        input = &input[len + 3..];
+        output = &mut out[1..];
+    }
+    output.copy_from_slice(input);
+
+    Ok(required)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALPHA_DIGIT: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+    fn is_unreserved(x: u8) -> bool {
+        ALPHA_DIGIT.contains(&x) || b"-._~".contains(&x)
+    }
+
+    fn is_sub_delim(x: u8) -> bool {
+        b"!$&'()*+,;=".contains(&x)
+    }
+
+    fn is_gen_delim(x: u8) -> bool {
+        b":/?#[]@".contains(&x)
+    }
+
+    fn is_pchar(x: u8) -> bool {
+        is_unreserved(x) || is_sub_delim(x) || b":@".contains(&x)
+    }
+
+    #[test]
+    fn unreserved_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                x.is_uri_unreserved_2() == is_unreserved(x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn sub_delim_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_uri_sub_delim_2() == is_sub_delim(x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn gen_delim_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_uri_gen_delim_2() == is_gen_delim(x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn pchar_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_uri_pchar_2() == is_pchar(x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn classes_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            let byte = u8::try_from(x).ok().filter(u8::is_ascii);
+            let expected = |f: fn(u8) -> bool| byte.is_some_and(f);
+            assert!(
+                x.is_uri_unreserved_2() == expected(is_unreserved),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                x.is_uri_sub_delim_2() == expected(is_sub_delim),
+                "Failed on {}",
+                x
+            );
+            assert!(
+                x.is_uri_gen_delim_2() == expected(is_gen_delim),
+                "Failed on {}",
+                x
+            );
+            assert!(x.is_uri_pchar_2() == expected(is_pchar), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn classes_slice() {
+        // The path segments of the examples in RFC 3986, section 1.1.2.
+        assert!("rfc1808.txt".is_uri_pchar_2());
+        assert!("comp.infosystems.www.servers.unix".is_uri_pchar_2());
+        assert!("John.Doe@example.com".is_uri_pchar_2());
+        assert!("+1-816-555-1212".is_uri_pchar_2());
+        assert!("oasis:names:specification:docbook:dtd:xml:4.1.2".is_uri_pchar_2());
+        assert!(!"c=GB?objectClass?one".is_uri_pchar_2());
+        assert!(!"rfc/rfc1808.txt".is_uri_pchar_2());
+
+        assert!("".is_uri_unreserved_2());
+        assert!("a-b.c_d~e".is_uri_unreserved_2());
+        assert!(!"a+b".is_uri_unreserved_2());
+        assert!("!$&'()*+,;=".is_uri_sub_delim_2());
+        assert!(":/?#[]@".as_bytes().is_uri_gen_delim_2());
+        assert!(!":/?#[]@!".as_bytes().is_uri_gen_delim_2());
+    }
+
+    #[test]
+    fn percent_encode_rfc_examples() {
+        let mut buffer = [0; 64];
+
+        // RFC 3986, section 6.2.2.2.
+        let len = percent_encode(b"{foo}", &mut buffer, &UriUnreserved).unwrap();
+        assert_eq!(&buffer[..len], b"%7Bfoo%7D");
+
+        // RFC 3986, section 2.1: a space is `%20`.
+        let len = percent_encode(b"a b", &mut buffer, &UriPchar).unwrap();
+        assert_eq!(&buffer[..len], b"a%20b");
+
+        let len = percent_encode(b"/%\xFF", &mut buffer, &UriPchar).unwrap();
+        assert_eq!(&buffer[..len], b"%2F%25%FF");
+
+        assert_eq!(
+            percent_encode(b"a b", &mut buffer[..4], &UriPchar),
+            Err(EncodeError::OutputTooSmall { required: 5 })
+        );
+    }
+
+    #[test]
+    fn percent_decode_rfc_examples() {
+        let mut buffer = [0; 64];
+
+        // RFC 3986, section 6.2.2: `%63` is `c`, and hexadecimal digits are case
+        // insensitive.
+        let len = percent_decode(b"%7Bfoo%7D", &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"{foo}");
+        let len = percent_decode(b"/b/%63/%7bfoo%7d", &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"/b/c/{foo}");
+        let len = percent_decode(b"%25%2F", &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"%/");
+
+        assert_eq!(
+            percent_decode(b"100%", &mut buffer),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            percent_decode(b"%4", &mut buffer),
+            Err(DecodeError::InvalidLength)
+        );
+        assert_eq!(
+            percent_decode(b"a%4g", &mut buffer),
+            Err(DecodeError::InvalidByte {
+                index: 3,
+                byte: b'g'
+            })
+        );
+        assert_eq!(
+            percent_decode(b"%%41", &mut buffer),
+            Err(DecodeError::InvalidByte {
+                index: 1,
+                byte: b'%'
+            })
+        );
+        assert_eq!(
+            percent_decode(b"%41%42", &mut buffer[..1]),
+            Err(DecodeError::OutputTooSmall { required: 2 })
+        );
+    }
+
+    #[test]
+    fn percent_round_trip() {
+        let input: [u8; 256] = core::array::from_fn(|i| i as u8);
+        let mut encoded = [0; 768];
+        let mut decoded = [0; 256];
+        let n = percent_encode(&input, &mut encoded, &UriUnreserved).unwrap();
+        assert!(!encoded[..n].is_uri_pchar_2());
+        assert_eq!(percent_decoded_len(&encoded[..n]), Ok(256));
+        let m = percent_decode(&encoded[..n], &mut decoded).unwrap();
+        assert_eq!(&decoded[..m], &input);
+    }
+
+    #[test]
+    fn hexdigit_values() {
+        for x in 0..=u8::MAX {
+            if x.is_ascii_hexdigit() {
+                assert_eq!(
+                    hexdigit_value(x),
+                    (x as char).to_digit(16).unwrap() as u8,
+                    "Failed on {}",
+                    x
+                );
+            }
+        }
+    }
+}
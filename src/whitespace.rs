@@ -0,0 +1,242 @@
+//! Definitions of whitespace other than the WHATWG one used by
+//! `is_ascii_whitespace_2`.
+
+pub trait IsWhitespace2 {
+    /// C's `isspace` in the `"C"` locale: space, `\t`, `\n`, `\x0B`, `\x0C`, and
+    /// `\r`.
+    fn is_c_space_2(&self) -> bool;
+    /// JSON's `ws` (RFC 8259, section 2): space, `\t`, `\n`, and `\r`.
+    fn is_json_whitespace_2(&self) -> bool;
+    /// XML's `S` (XML 1.0, section 2.3): space, `\t`, `\n`, and `\r`.
+    fn is_xml_whitespace_2(&self) -> bool;
+    /// C's `isblank` in the `"C"` locale: space and `\t`.
+    fn is_blank_2(&self) -> bool;
+}
+
+// The long binary numbers have bit indexes starting at 0 on the right and going
+// leftward until they end at bit index 32. The bit index corresponds to the
+// codepoint of the input. The value of the bit there is 1 iff the codepoint is
+// whitespace under that definition.
+const C_SPACE: u64 = 0b1_0000_0000_0000_0000_0011_1110_0000_0000;
+const JSON_WHITESPACE: u64 = 0b1_0000_0000_0000_0000_0010_0110_0000_0000;
+const XML_WHITESPACE: u64 = 0b1_0000_0000_0000_0000_0010_0110_0000_0000;
+const BLANK: u64 = 0b1_0000_0000_0000_0000_0000_0010_0000_0000;
+
+impl IsWhitespace2 for u8 {
+    #[inline]
+    fn is_c_space_2(&self) -> bool {
+        let x = *self;
+        x <= b' ' && ((C_SPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_json_whitespace_2(&self) -> bool {
+        let x = *self;
+        x <= b' ' && ((JSON_WHITESPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_xml_whitespace_2(&self) -> bool {
+        let x = *self;
+        x <= b' ' && ((XML_WHITESPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_blank_2(&self) -> bool {
+        let x = *self;
+        x <= b' ' && ((BLANK >> x) & 1) != 0
+    }
+}
+
+impl IsWhitespace2 for char {
+    #[inline]
+    fn is_c_space_2(&self) -> bool {
+        let x = *self as u32;
+        x <= ' ' as u32 && ((C_SPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_json_whitespace_2(&self) -> bool {
+        let x = *self as u32;
+        x <= ' ' as u32 && ((JSON_WHITESPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_xml_whitespace_2(&self) -> bool {
+        let x = *self as u32;
+        x <= ' ' as u32 && ((XML_WHITESPACE >> x) & 1) != 0
+    }
+
+    #[inline]
+    fn is_blank_2(&self) -> bool {
+        let x = *self as u32;
+        x <= ' ' as u32 && ((BLANK >> x) & 1) != 0
+    }
+}
+
+impl IsWhitespace2 for [u8] {
+    #[inline]
+    fn is_c_space_2(&self) -> bool {
+        self.iter().all(u8::is_c_space_2)
+    }
+
+    #[inline]
+    fn is_json_whitespace_2(&self) -> bool {
+        self.iter().all(u8::is_json_whitespace_2)
+    }
+
+    #[inline]
+    fn is_xml_whitespace_2(&self) -> bool {
+        self.iter().all(u8::is_xml_whitespace_2)
+    }
+
+    #[inline]
+    fn is_blank_2(&self) -> bool {
+        self.iter().all(u8::is_blank_2)
+    }
+}
+
+impl IsWhitespace2 for str {
+    #[inline]
+    fn is_c_space_2(&self) -> bool {
+        self.as_bytes().is_c_space_2()
+    }
+
+    #[inline]
+    fn is_json_whitespace_2(&self) -> bool {
+        self.as_bytes().is_json_whitespace_2()
+    }
+
+    #[inline]
+    fn is_xml_whitespace_2(&self) -> bool {
+        self.as_bytes().is_xml_whitespace_2()
+    }
+
+    #[inline]
+    fn is_blank_2(&self) -> bool {
+        self.as_bytes().is_blank_2()
+    }
+}
+
+crate::class::class_types! {
+    /// The class of `is_c_space_2`.
+    CSpace => is_c_space_2,
+    /// The class of `is_json_whitespace_2`.
+    JsonWhitespace => is_json_whitespace_2,
+    /// The class of `is_xml_whitespace_2`.
+    XmlWhitespace => is_xml_whitespace_2,
+    /// The class of `is_blank_2`.
+    Blank => is_blank_2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsWhitespace2;
+
+    // The tables from the documents cited on each method.
+    const C_SPACE: &[u8] = b" \t\n\x0B\x0C\r";
+    const JSON_WHITESPACE: &[u8] = b" \t\n\r";
+    const XML_WHITESPACE: &[u8] = b" \t\n\r";
+    const BLANK: &[u8] = b" \t";
+
+    #[test]
+    fn c_space_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_c_space_2() == C_SPACE.contains(&x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn json_whitespace_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                x.is_json_whitespace_2() == JSON_WHITESPACE.contains(&x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn xml_whitespace_u8() {
+        for x in 0..=u8::MAX {
+            assert!(
+                x.is_xml_whitespace_2() == XML_WHITESPACE.contains(&x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn blank_u8() {
+        for x in 0..=u8::MAX {
+            assert!(x.is_blank_2() == BLANK.contains(&x), "Failed on {}", x);
+        }
+    }
+
+    fn in_table(table: &[u8], x: char) -> bool {
+        x.is_ascii() && table.contains(&(x as u8))
+    }
+
+    #[test]
+    fn c_space_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(x.is_c_space_2() == in_table(C_SPACE, x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn json_whitespace_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                x.is_json_whitespace_2() == in_table(JSON_WHITESPACE, x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn xml_whitespace_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(
+                x.is_xml_whitespace_2() == in_table(XML_WHITESPACE, x),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn blank_char() {
+        for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+            assert!(x.is_blank_2() == in_table(BLANK, x), "Failed on {}", x);
+        }
+    }
+
+    #[test]
+    fn whatwg_differs_from_c_only_in_vertical_tab() {
+        use crate::IsAscii2;
+
+        for x in 0..=u8::MAX {
+            assert!(
+                x.is_ascii_whitespace_2() == (x.is_c_space_2() && x != b'\x0B'),
+                "Failed on {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn slices() {
+        assert!("".is_c_space_2());
+        assert!(" \t\n\x0B\x0C\r".is_c_space_2());
+        assert!(!" \t\n\x0B\x0C\r".is_json_whitespace_2());
+        assert!(" \t\r\n".is_json_whitespace_2());
+        assert!(" \t\r\n".as_bytes().is_xml_whitespace_2());
+        assert!(!"\u{A0}".is_xml_whitespace_2());
+        assert!(" \t \t".is_blank_2());
+        assert!(!" \n".as_bytes().is_blank_2());
+    }
+}
@@ -0,0 +1,88 @@
+use is_ascii_speedups::AsciiClass;
+use is_ascii_speedups_macros::ascii_class;
+
+ascii_class! {
+    /// A single range.
+    Digit = [0-9];
+    /// A range after a case fold.
+    Alpha = [[:alpha:]];
+    /// A 64-bit shift mask.
+    Blank = "[ \t]";
+    Quote = "[\"'`]";
+    /// One strip per 32-codepoint chunk.
+    pub Slug = [0-9a-zA-Z_-];
+    /// One strip per 32-codepoint chunk, after an offset.
+    pub(crate) Punct = "[!-/:-@[-`{-~]";
+    /// One strip per 16-codepoint chunk, after an offset.
+    Base64 = [A-Za-z0-9+/];
+    /// The bytes from 128 up.
+    High = [^[:print:][:cntrl:]];
+    NotSpace = [^[:space:]];
+    /// The 256-bit bitmap fallback.
+    NotB = [^b]
+}
+
+fn check(
+    class: impl AsciiClass + Copy,
+    contains_u16: impl Fn(u16) -> bool,
+    is_member: impl Fn(u8) -> bool,
+) {
+    for x in 0..=u8::MAX {
+        assert_eq!(class.contains(x), is_member(x), "Failed on {}", x);
+    }
+    for x in 0..=u16::MAX {
+        let expected = u8::try_from(x).is_ok_and(&is_member);
+        assert_eq!(contains_u16(x), expected, "Failed on {}", x);
+    }
+    for x in ('\0'..='\u{d7ff}').chain('\u{e000}'..='\u{10ffff}') {
+        let expected = u8::try_from(x).is_ok_and(&is_member);
+        assert_eq!(class.contains_char(x), expected, "Failed on {}", x);
+    }
+    let members: Vec<u8> = (0..=u8::MAX).filter(|&x| is_member(x)).collect();
+    assert!(class.contains_all(&members));
+    if members.len() < 256 {
+        assert!(!class.contains_all(&(0..=u8::MAX).collect::<Vec<_>>()));
+    }
+}
+
+#[test]
+fn every_technique() {
+    check(Digit, |x| Digit.contains_u16(x), |x| x.is_ascii_digit());
+    check(
+        Alpha,
+        |x| Alpha.contains_u16(x),
+        |x| x.is_ascii_alphabetic(),
+    );
+    check(
+        Blank,
+        |x| Blank.contains_u16(x),
+        |x| x == b' ' || x == b'\t',
+    );
+    check(Quote, |x| Quote.contains_u16(x), |x| b"\"'`".contains(&x));
+    check(
+        Slug,
+        |x| Slug.contains_u16(x),
+        |x| x.is_ascii_alphanumeric() || x == b'_' || x == b'-',
+    );
+    check(
+        Punct,
+        |x| Punct.contains_u16(x),
+        |x| x.is_ascii_punctuation(),
+    );
+    check(
+        Base64,
+        |x| Base64.contains_u16(x),
+        |x| x.is_ascii_alphanumeric() || x == b'+' || x == b'/',
+    );
+    check(High, |x| High.contains_u16(x), |x| !x.is_ascii());
+    check(
+        NotSpace,
+        |x| NotSpace.contains_u16(x),
+        |x| !x.is_ascii_whitespace() && x != b'\x0B',
+    );
+    check(NotB, |x| NotB.contains_u16(x), |x| x != b'b');
+}
+
+// `contains_u16` is a `const fn`.
+const _: () = assert!(Digit.contains_u16(b'7' as u16));
+const _: () = assert!(!Base64.contains_u16(0x100 + b'A' as u16));
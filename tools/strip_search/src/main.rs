@@ -0,0 +1,65 @@
+//! A development tool that finds a branchless encoding for a set of bytes, the way
+//! `is_ascii_punctuation_2`'s `+ 6` offset was found by hand.
+//!
+//! ```text
+//! cargo run --release -p strip_search -- is_ascii_punctuation_2 '!-/:-@[-`{-~'
+//! ```
+//!
+//! The set is written like the inside of a regex bracket expression, with `\x`
+//! escapes, or as a whole POSIX bracket expression like `'[[:alpha:]_]'`, the syntax
+//! of `ascii_class!`. The tool tries OR-masks like the `| 0b0010_0000` case fold,
+//! offsets, and 32-, 16-, 8- and 4-byte chunks for `handle_strip_of_each_chunk!`,
+//! along with plain ranges and bitmaps. It prints every technique that applies,
+//! cheapest first, then the `u8` and `char` methods for the cheapest one and
+//! exhaustive tests for them, ready to paste into an `IsAscii2`-style trait.
+
+#[path = "../../../macros/src/search.rs"]
+mod search;
+
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [name, spec] = &args[..] else {
+        eprintln!("usage: strip_search <method name> <set>");
+        eprintln!("example: strip_search is_ascii_punctuation_2 '!-/:-@[-`{{-~'");
+        return ExitCode::FAILURE;
+    };
+    let parsed = if spec.starts_with('[') {
+        search::parse_bracket(spec)
+    } else {
+        search::parse_set(spec)
+    };
+    let set = match parsed {
+        Ok(set) => set,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let encodings = search::search(&set);
+    for encoding in &encodings {
+        assert!(
+            (0..=255).all(|x| encoding.contains(x) == set[x as usize]),
+            "{:?} doesn't match the set",
+            encoding
+        );
+    }
+    println!("// Techniques that apply, cheapest first:");
+    for encoding in &encodings {
+        println!("// - {}", encoding.describe());
+    }
+    println!();
+    // This only happens for sets that span more than 128 bytes and that no chunk size
+    // splits into strips.
+    let Some(best) = encodings.first() else {
+        println!("// No branchless encoding found. Use a 256-bit table instead.");
+        return ExitCode::SUCCESS;
+    };
+    print!("{}", best.methods(&set, name));
+    println!("// #[cfg(test)] mod tests");
+    print!("{}", search::tests(&set, name));
+    ExitCode::SUCCESS
+}